@@ -1,14 +1,7 @@
 // Copyright 2020 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-/// An output is considered dust if its amount is strictly less than this value.
-pub const DUST_THRESHOLD: u64 = 1_000_000;
-
-/// Divisor used to compute the allowed dust outputs on an address.
-pub const DUST_ALLOWANCE_DIVISOR: u64 = 100_000;
-
-/// Maximum number of dust outputs for an address.
-pub const DUST_OUTPUTS_MAX: u64 = 100;
+pub use bee_message::constants::{DUST_ALLOWANCE_DIVISOR, DUST_OUTPUTS_MAX, DUST_THRESHOLD};
 
 /// `dust_allowance_sum` is the sum of all the amounts of all unspent SigLockedDustAllowanceOutputs on an address.
 /// The maximum number of allowed dust outputs on this address is `dust_allowance_sum` divided by
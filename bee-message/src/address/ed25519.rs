@@ -1,7 +1,7 @@
 // Copyright 2020 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{unlock::Ed25519Signature, Error};
+use crate::{error::hex_decode_error, unlock::Ed25519Signature, Error};
 
 use bee_common::packable::{Packable, Read, Write};
 
@@ -69,7 +69,7 @@ impl FromStr for Ed25519Address {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let bytes: [u8; ED25519_ADDRESS_LENGTH] = hex::decode(s)
-            .map_err(|_| Self::Err::InvalidHexadecimalChar(s.to_owned()))?
+            .map_err(|e| hex_decode_error(e, s, ED25519_ADDRESS_LENGTH * 2))?
             .try_into()
             .map_err(|_| Self::Err::InvalidHexadecimalLength(ED25519_ADDRESS_LENGTH * 2, s.len()))?;
 
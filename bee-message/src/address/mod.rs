@@ -32,14 +32,27 @@ impl Address {
         }
     }
 
-    pub fn try_from_bech32(addr: &str) -> Result<Self, Error> {
-        match bech32::decode(addr) {
-            Ok((_hrp, data, _)) => {
-                let bytes = Vec::<u8>::from_base32(&data).map_err(|_| Error::InvalidAddress)?;
-                Self::unpack(&mut bytes.as_slice()).map_err(|_| Error::InvalidAddress)
-            }
-            Err(_) => Err(Error::InvalidAddress),
+    /// Decodes a Bech32 encoded address, returning its human-readable part along with the address itself.
+    pub fn from_bech32(addr: &str) -> Result<(String, Self), Error> {
+        let (hrp, data, variant) = bech32::decode(addr).map_err(|_| Error::InvalidAddress)?;
+
+        if variant != Variant::Bech32 {
+            return Err(Error::InvalidAddress);
+        }
+
+        let bytes = Vec::<u8>::from_base32(&data).map_err(|_| Error::InvalidAddress)?;
+        let mut reader = bytes.as_slice();
+        let address = Self::unpack(&mut reader).map_err(|_| Error::InvalidAddress)?;
+
+        if !reader.is_empty() {
+            return Err(Error::InvalidAddress);
         }
+
+        Ok((hrp, address))
+    }
+
+    pub fn try_from_bech32(addr: &str) -> Result<Self, Error> {
+        Self::from_bech32(addr).map(|(_, address)| address)
     }
 
     pub fn to_bech32(&self, hrp: &str) -> String {
@@ -8,4 +8,13 @@ pub const IOTA_SUPPLY: u64 = 2_779_530_283_277_761;
 pub const INPUT_OUTPUT_COUNT_MAX: usize = 127;
 pub const INPUT_OUTPUT_COUNT_RANGE: RangeInclusive<usize> = 1..=INPUT_OUTPUT_COUNT_MAX;
 pub const UNLOCK_BLOCK_COUNT_RANGE: RangeInclusive<usize> = INPUT_OUTPUT_COUNT_RANGE;
-pub const INPUT_OUTPUT_INDEX_RANGE: Range<u16> = 0..INPUT_OUTPUT_COUNT_MAX as u16;
+/// Exclusive upper bound of the index of an input or output.
+pub const OUTPUT_INDEX_MAX: u16 = INPUT_OUTPUT_COUNT_MAX as u16;
+pub const INPUT_OUTPUT_INDEX_RANGE: Range<u16> = 0..OUTPUT_INDEX_MAX;
+
+/// An output is considered dust if its amount is strictly less than this value.
+pub const DUST_THRESHOLD: u64 = 1_000_000;
+/// Divisor used to compute the allowed dust outputs on an address.
+pub const DUST_ALLOWANCE_DIVISOR: u64 = 100_000;
+/// Maximum number of dust outputs for an address.
+pub const DUST_OUTPUTS_MAX: u64 = 100;
@@ -1,6 +1,8 @@
 // Copyright 2020 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::MessageId;
+
 use core::fmt;
 
 #[derive(Debug)]
@@ -22,6 +24,7 @@ pub enum Error {
     InvalidSignatureKind(u8),
     InvalidUnlockBlockKind(u8),
     InvalidAccumulatedOutput(u128),
+    InvalidInputsCommitment,
     InputUnlockBlockCountMismatch(usize, usize),
     InvalidParentsCount(usize),
     DuplicateError,
@@ -40,18 +43,29 @@ pub enum Error {
     MilestoneInvalidSignatureCount(usize),
     MilestonePublicKeysSignaturesCountMismatch(usize, usize),
     InvalidUnlockBlockReference(usize),
+    MissingUnlockBlock(usize),
     DuplicateSignature(usize),
     TransactionInputsNotSorted,
     TransactionOutputsNotSorted,
     MigratedFundsNotSorted,
     RemainingBytesAfterMessage,
-    ParentsNotUniqueSorted,
+    DuplicateParent(MessageId),
+    ParentsNotSorted(MessageId, MessageId),
     TailTransactionHashNotUnique(usize, usize),
     SignaturePublicKeyMismatch(String, String),
     InvalidSignature,
+    DustOutputWithoutAllowance,
+    InsufficientDustAllowance(u64, u64),
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -75,6 +89,7 @@ impl fmt::Display for Error {
             Error::InvalidSignatureKind(k) => write!(f, "Invalid signature kind: {}.", k),
             Error::InvalidUnlockBlockKind(k) => write!(f, "Invalid unlock block kind: {}.", k),
             Error::InvalidAccumulatedOutput(value) => write!(f, "Invalid accumulated output balance: {}.", value),
+            Error::InvalidInputsCommitment => write!(f, "Invalid inputs commitment."),
             Error::InputUnlockBlockCountMismatch(input, block) => {
                 write!(
                     f,
@@ -94,7 +109,7 @@ impl fmt::Display for Error {
             Error::MissingPayload => write!(f, "Missing payload."),
             Error::InvalidHexadecimalChar(hex) => write!(f, "Invalid hexadecimal character: {}.", hex),
             Error::InvalidHexadecimalLength(expected, actual) => {
-                write!(f, "Invalid hexadecimal length: expected {} got {}.", expected, actual)
+                write!(f, "Invalid hexadecimal length: expected {}, got {}.", expected, actual)
             }
             Error::InvalidIndexationIndexLength(length) => {
                 write!(f, "Invalid indexation index length {}.", length)
@@ -123,6 +138,9 @@ impl fmt::Display for Error {
             Error::InvalidUnlockBlockReference(index) => {
                 write!(f, "Invalid unlock block reference: {0}", index)
             }
+            Error::MissingUnlockBlock(index) => {
+                write!(f, "Missing unlock block at index: {0}", index)
+            }
             Error::DuplicateSignature(index) => {
                 write!(f, "Duplicate signature at index: {0}", index)
             }
@@ -138,8 +156,9 @@ impl fmt::Display for Error {
             Error::RemainingBytesAfterMessage => {
                 write!(f, "Remaining bytes after message.")
             }
-            Error::ParentsNotUniqueSorted => {
-                write!(f, "Parents not unique and/or sorted.")
+            Error::DuplicateParent(parent) => write!(f, "Duplicate parent: {}.", parent),
+            Error::ParentsNotSorted(previous, next) => {
+                write!(f, "Parents not sorted: {} before {}.", previous, next)
             }
             Error::TailTransactionHashNotUnique(previous, current) => {
                 write!(
@@ -156,6 +175,10 @@ impl fmt::Display for Error {
                 )
             }
             Error::InvalidSignature => write!(f, "Invalid signature provided."),
+            Error::DustOutputWithoutAllowance => write!(f, "Dust output without dust allowance output."),
+            Error::InsufficientDustAllowance(allowance, required) => {
+                write!(f, "Insufficient dust allowance: {}, required: {}.", allowance, required)
+            }
         }
     }
 }
@@ -165,3 +188,14 @@ impl From<std::io::Error> for Error {
         Error::Io(error)
     }
 }
+
+// Maps a failure to decode `hex`, expected to be `expected` characters long, to the matching error. Odd lengths are
+// reported as such rather than as invalid characters.
+pub(crate) fn hex_decode_error(error: hex::FromHexError, hex: &str, expected: usize) -> Error {
+    match error {
+        hex::FromHexError::OddLength | hex::FromHexError::InvalidStringLength => {
+            Error::InvalidHexadecimalLength(expected, hex.len())
+        }
+        hex::FromHexError::InvalidHexCharacter { .. } => Error::InvalidHexadecimalChar(hex.to_owned()),
+    }
+}
@@ -13,6 +13,7 @@ pub struct UtxoInput(OutputId);
 impl UtxoInput {
     pub const KIND: u8 = 0;
 
+    /// Creates a new `UtxoInput`, the index must be strictly less than `OUTPUT_INDEX_MAX`.
     pub fn new(id: TransactionId, index: u16) -> Result<Self, Error> {
         Ok(Self(OutputId::new(id, index)?))
     }
@@ -25,6 +26,18 @@ impl UtxoInput {
 #[cfg(feature = "serde")]
 string_serde_impl!(UtxoInput);
 
+#[cfg(feature = "proptest")]
+impl proptest::arbitrary::Arbitrary for UtxoInput {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use proptest::{arbitrary::any, strategy::Strategy};
+
+        any::<OutputId>().prop_map(Self::from).boxed()
+    }
+}
+
 impl From<OutputId> for UtxoInput {
     fn from(id: OutputId) -> Self {
         UtxoInput(id)
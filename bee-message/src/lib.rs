@@ -23,6 +23,6 @@ pub mod solid_entry_point;
 pub mod unlock;
 
 pub use error::Error;
-pub use message::{Message, MessageBuilder, MESSAGE_LENGTH_MAX, MESSAGE_LENGTH_MIN};
+pub use message::{pack_framed, unpack_framed, Message, MessageBuilder, MESSAGE_LENGTH_MAX, MESSAGE_LENGTH_MIN};
 pub use message_id::{MessageId, MESSAGE_ID_LENGTH};
 pub use parents::Parents;
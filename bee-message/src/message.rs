@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    payload::{option_payload_pack, option_payload_packed_len, option_payload_unpack, Payload},
+    payload::{option_payload_pack, option_payload_packed_len, option_payload_unpack, transaction::Essence, Payload},
     Error, MessageId, Parents,
 };
 
@@ -10,6 +10,7 @@ use bee_common::packable::{Packable, Read, Write};
 use bee_pow::providers::{Miner, Provider, ProviderBuilder};
 
 use crypto::hashes::{blake2b::Blake2b256, Digest};
+use once_cell::sync::OnceCell;
 
 use std::sync::{atomic::AtomicBool, Arc};
 
@@ -23,6 +24,8 @@ pub struct Message {
     parents: Parents,
     payload: Option<Payload>,
     nonce: u64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    hash: OnceCell<MessageId>,
 }
 
 impl Message {
@@ -38,6 +41,11 @@ impl Message {
         (MessageId::new(id.into()), bytes)
     }
 
+    /// Returns the id of the message, only computing it the first time.
+    pub fn hash(&self) -> MessageId {
+        *self.hash.get_or_init(|| self.id().0)
+    }
+
     pub fn network_id(&self) -> u64 {
         self.network_id
     }
@@ -50,6 +58,10 @@ impl Message {
         &self.payload
     }
 
+    pub fn payload_kind(&self) -> Option<u32> {
+        self.payload.as_ref().map(Payload::kind)
+    }
+
     pub fn nonce(&self) -> u64 {
         self.nonce
     }
@@ -109,10 +121,33 @@ impl Packable for Message {
             parents,
             payload,
             nonce,
+            hash: OnceCell::new(),
         })
     }
 }
 
+/// Packs a message into a frame made of its packed length, as a `u32`, followed by the packed message.
+pub fn pack_framed<W: Write>(message: &Message, writer: &mut W) -> Result<(), Error> {
+    (message.packed_len() as u32).pack(writer)?;
+    message.pack(writer)?;
+
+    Ok(())
+}
+
+/// Unpacks a message from a frame written by `pack_framed`, rejecting frames longer than `MESSAGE_LENGTH_MAX`.
+pub fn unpack_framed<R: Read + ?Sized>(reader: &mut R) -> Result<Message, Error> {
+    let message_len = u32::unpack(reader)? as usize;
+
+    if message_len > MESSAGE_LENGTH_MAX {
+        return Err(Error::InvalidMessageLength(message_len));
+    }
+
+    let mut bytes = vec![0u8; message_len];
+    reader.read_exact(&mut bytes)?;
+
+    Message::unpack(&mut bytes.as_slice())
+}
+
 pub struct MessageBuilder<P: Provider = Miner> {
     network_id: Option<u64>,
     parents: Option<Parents>,
@@ -169,11 +204,18 @@ impl<P: Provider> MessageBuilder<P> {
             return Err(Error::InvalidPayloadKind(self.payload.unwrap().kind()));
         }
 
+        if let Some(Payload::Transaction(transaction)) = self.payload.as_ref() {
+            match transaction.essence() {
+                Essence::Regular(essence) => essence.validate_outputs()?,
+            }
+        }
+
         let mut message = Message {
             network_id,
             parents,
             payload: self.payload,
             nonce: 0,
+            hash: OnceCell::new(),
         };
 
         let message_bytes = message.pack_new();
@@ -1,7 +1,7 @@
 // Copyright 2020 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::Error;
+use crate::{error::hex_decode_error, Error};
 
 use bee_common::packable::{Packable, Read, Write};
 
@@ -13,6 +13,9 @@ pub const MESSAGE_ID_LENGTH: usize = 32;
 pub struct MessageId([u8; MESSAGE_ID_LENGTH]);
 
 impl MessageId {
+    /// The id of the genesis message.
+    pub const GENESIS: MessageId = MessageId([0u8; MESSAGE_ID_LENGTH]);
+
     pub fn new(bytes: [u8; MESSAGE_ID_LENGTH]) -> Self {
         bytes.into()
     }
@@ -20,11 +23,23 @@ impl MessageId {
     pub fn null() -> Self {
         Self([0u8; MESSAGE_ID_LENGTH])
     }
+
+    pub fn is_genesis(&self) -> bool {
+        *self == Self::GENESIS
+    }
 }
 
 #[cfg(feature = "serde")]
 string_serde_impl!(MessageId);
 
+// `ZeroizeOnDrop` can't be provided as the type is `Copy` and therefore can't implement `Drop`.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for MessageId {
+    fn zeroize(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
 impl From<[u8; MESSAGE_ID_LENGTH]> for MessageId {
     fn from(bytes: [u8; MESSAGE_ID_LENGTH]) -> Self {
         Self(bytes)
@@ -36,7 +51,7 @@ impl FromStr for MessageId {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let bytes: [u8; MESSAGE_ID_LENGTH] = hex::decode(s)
-            .map_err(|_| Self::Err::InvalidHexadecimalChar(s.to_owned()))?
+            .map_err(|e| hex_decode_error(e, s, MESSAGE_ID_LENGTH * 2))?
             .try_into()
             .map_err(|_| Self::Err::InvalidHexadecimalLength(MESSAGE_ID_LENGTH * 2, s.len()))?;
 
@@ -38,6 +38,16 @@ impl Output {
             Self::Treasury(_) => TreasuryOutput::KIND,
         }
     }
+
+    /// Returns the amount of tokens held by the output in the UTXO ledger. Treasury outputs are not part of it, so
+    /// their amount is always 0 here.
+    pub fn amount(&self) -> u64 {
+        match self {
+            Self::SignatureLockedSingle(output) => output.amount(),
+            Self::SignatureLockedDustAllowance(output) => output.amount(),
+            Self::Treasury(_) => 0,
+        }
+    }
 }
 
 impl From<SignatureLockedSingleOutput> for Output {
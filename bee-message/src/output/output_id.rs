@@ -3,6 +3,7 @@
 
 use crate::{
     constants::INPUT_OUTPUT_INDEX_RANGE,
+    error::hex_decode_error,
     payload::transaction::{TransactionId, TRANSACTION_ID_LENGTH},
     Error,
 };
@@ -23,6 +24,8 @@ pub struct OutputId {
 }
 
 impl OutputId {
+    /// Creates a new `OutputId`.
+    /// The index must be strictly less than `OUTPUT_INDEX_MAX`, otherwise `Error::InvalidInputOutputIndex` is returned.
     pub fn new(transaction_id: TransactionId, index: u16) -> Result<Self, Error> {
         if !INPUT_OUTPUT_INDEX_RANGE.contains(&index) {
             return Err(Error::InvalidInputOutputIndex(index));
@@ -42,11 +45,39 @@ impl OutputId {
     pub fn split(self) -> (TransactionId, u16) {
         (self.transaction_id, self.index)
     }
+
+    pub fn from_bytes(bytes: [u8; OUTPUT_ID_LENGTH]) -> Result<Self, Error> {
+        Self::try_from(bytes)
+    }
+
+    pub fn to_bytes(&self) -> [u8; OUTPUT_ID_LENGTH] {
+        let mut bytes = [0u8; OUTPUT_ID_LENGTH];
+
+        bytes[..TRANSACTION_ID_LENGTH].copy_from_slice(self.transaction_id.as_ref());
+        bytes[TRANSACTION_ID_LENGTH..].copy_from_slice(&self.index.to_le_bytes());
+
+        bytes
+    }
 }
 
 #[cfg(feature = "serde")]
 string_serde_impl!(OutputId);
 
+#[cfg(feature = "proptest")]
+impl proptest::arbitrary::Arbitrary for OutputId {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use proptest::{arbitrary::any, strategy::Strategy};
+
+        (any::<TransactionId>(), INPUT_OUTPUT_INDEX_RANGE)
+            // Unwrap is fine because the index is generated within the valid range.
+            .prop_map(|(transaction_id, index)| Self::new(transaction_id, index).unwrap())
+            .boxed()
+    }
+}
+
 impl TryFrom<[u8; OUTPUT_ID_LENGTH]> for OutputId {
     type Error = Error;
 
@@ -67,7 +98,7 @@ impl FromStr for OutputId {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let bytes: [u8; OUTPUT_ID_LENGTH] = hex::decode(s)
-            .map_err(|_| Self::Err::InvalidHexadecimalChar(s.to_owned()))?
+            .map_err(|e| hex_decode_error(e, s, OUTPUT_ID_LENGTH * 2))?
             .try_into()
             .map_err(|_| Self::Err::InvalidHexadecimalLength(OUTPUT_ID_LENGTH * 2, s.len()))?;
 
@@ -20,11 +20,19 @@ impl SignatureLockedSingleOutput {
     pub const KIND: u8 = 0;
 
     pub fn new(address: Address, amount: u64) -> Result<Self, Error> {
-        if !SIGNATURE_LOCKED_SINGLE_OUTPUT_AMOUNT.contains(&amount) {
-            return Err(Error::InvalidAmount(amount));
+        let output = Self { address, amount };
+
+        output.validate()?;
+
+        Ok(output)
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        if !SIGNATURE_LOCKED_SINGLE_OUTPUT_AMOUNT.contains(&self.amount) {
+            return Err(Error::InvalidAmount(self.amount));
         }
 
-        Ok(Self { address, amount })
+        Ok(())
     }
 
     pub fn address(&self) -> &Address {
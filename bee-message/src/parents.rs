@@ -3,12 +3,13 @@
 
 use crate::{Error, MessageId, MESSAGE_ID_LENGTH};
 
-use bee_common::{
-    ord::is_unique_sorted,
-    packable::{Packable, Read, Write},
-};
+use bee_common::packable::{Packable, Read, Write};
 
-use core::ops::{Deref, RangeInclusive};
+use core::{
+    cmp::Ordering,
+    convert::TryFrom,
+    ops::{Deref, RangeInclusive},
+};
 
 pub const MESSAGE_PARENTS_RANGE: RangeInclusive<usize> = 1..=8;
 
@@ -31,13 +32,26 @@ impl Parents {
             return Err(Error::InvalidParentsCount(inner.len()));
         }
 
-        if !is_unique_sorted(inner.iter().map(AsRef::as_ref)) {
-            return Err(Error::ParentsNotUniqueSorted);
+        // Parents must be lexicographically sorted and unique.
+        for pair in inner.windows(2) {
+            match pair[0].cmp(&pair[1]) {
+                Ordering::Less => (),
+                Ordering::Equal => return Err(Error::DuplicateParent(pair[0])),
+                Ordering::Greater => return Err(Error::ParentsNotSorted(pair[0], pair[1])),
+            }
         }
 
         Ok(Self(inner))
     }
 
+    /// Creates `Parents` from message ids in any order, sorting and deduplicating them first.
+    pub fn from_unsorted(mut inner: Vec<MessageId>) -> Result<Self, Error> {
+        inner.sort_unstable();
+        inner.dedup();
+
+        Self::new(inner)
+    }
+
     pub fn len(&self) -> usize {
         self.0.len()
     }
@@ -47,6 +61,14 @@ impl Parents {
     }
 }
 
+impl TryFrom<Vec<MessageId>> for Parents {
+    type Error = Error;
+
+    fn try_from(inner: Vec<MessageId>) -> Result<Self, Self::Error> {
+        Self::new(inner)
+    }
+}
+
 impl Packable for Parents {
     type Error = Error;
 
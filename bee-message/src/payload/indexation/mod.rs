@@ -49,6 +49,11 @@ impl IndexationPayload {
         &self.data
     }
 
+    /// Returns the index, also known as tag, as a string slice if it is valid UTF-8.
+    pub fn tag_as_utf8(&self) -> Option<&str> {
+        core::str::from_utf8(&self.index).ok()
+    }
+
     pub fn hash(&self) -> HashedIndex {
         HashedIndex::new(Blake2b256::digest(&self.index).into())
     }
@@ -1,7 +1,7 @@
 // Copyright 2020 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::Error;
+use crate::{error::hex_decode_error, Error};
 
 use bee_common::packable::{Packable, Read, Write};
 
@@ -36,7 +36,7 @@ impl FromStr for MilestoneId {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let bytes: [u8; MILESTONE_ID_LENGTH] = hex::decode(s)
-            .map_err(|_| Self::Err::InvalidHexadecimalChar(s.to_owned()))?
+            .map_err(|e| hex_decode_error(e, s, MILESTONE_ID_LENGTH * 2))?
             .try_into()
             .map_err(|_| Self::Err::InvalidHexadecimalLength(MILESTONE_ID_LENGTH * 2, s.len()))?;
 
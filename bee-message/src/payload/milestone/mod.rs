@@ -71,6 +71,41 @@ impl MilestonePayload {
         &self.signatures
     }
 
+    /// Verifies every signature of the milestone against the essence hash, using the matching key among `public_keys`.
+    pub fn verify_signatures(&self, public_keys: &[ed25519::PublicKey]) -> Result<(), MilestoneValidationError> {
+        let essence_hash = self.essence().hash();
+
+        for (index, (public_key, signature)) in self
+            .essence()
+            .public_keys()
+            .iter()
+            .zip(self.signatures.iter())
+            .enumerate()
+        {
+            let ed25519_public_key = public_keys
+                .iter()
+                .find(|k| k.to_compressed_bytes() == *public_key)
+                .ok_or_else(|| MilestoneValidationError::UnapplicablePublicKey(hex::encode(public_key)))?;
+            let ed25519_signature = ed25519::Signature::from_bytes(
+                signature
+                    .as_ref()
+                    .try_into()
+                    .map_err(|_| MilestoneValidationError::InvalidSignature(index, hex::encode(public_key)))?,
+            );
+
+            if !ed25519_public_key.verify(&ed25519_signature, &essence_hash) {
+                return Err(MilestoneValidationError::InvalidSignature(
+                    index,
+                    hex::encode(public_key),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates the milestone against the hex-encoded applicable public keys, requiring at least `min_threshold`
+    /// signatures, then verifies its signatures like `verify_signatures`.
     pub fn validate(
         &self,
         applicable_public_keys: &[String],
@@ -94,34 +129,21 @@ impl MilestonePayload {
             ));
         }
 
-        let essence_hash = self.essence().hash();
-
-        for (index, (public_key, signature)) in self
+        let public_keys = self
             .essence()
             .public_keys()
             .iter()
-            .zip(self.signatures.iter())
-            .enumerate()
-        {
-            // TODO use concrete ED25 types
-            if !applicable_public_keys.contains(&hex::encode(public_key)) {
-                return Err(MilestoneValidationError::UnapplicablePublicKey(hex::encode(public_key)));
-            }
+            .map(|public_key| {
+                if !applicable_public_keys.contains(&hex::encode(public_key)) {
+                    return Err(MilestoneValidationError::UnapplicablePublicKey(hex::encode(public_key)));
+                }
 
-            // TODO unwrap
-            let ed25519_public_key = ed25519::PublicKey::from_compressed_bytes(*public_key).unwrap();
-            // TODO unwrap
-            let ed25519_signature = ed25519::Signature::from_bytes(signature.as_ref().try_into().unwrap());
+                ed25519::PublicKey::from_compressed_bytes(*public_key)
+                    .map_err(|_| MilestoneValidationError::UnapplicablePublicKey(hex::encode(public_key)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
-            if !ed25519_public_key.verify(&ed25519_signature, &essence_hash) {
-                return Err(MilestoneValidationError::InvalidSignature(
-                    index,
-                    hex::encode(public_key),
-                ));
-            }
-        }
-
-        Ok(())
+        self.verify_signatures(&public_keys)
     }
 }
 
@@ -44,6 +44,11 @@ impl Payload {
             Self::TreasuryTransaction(_) => TreasuryTransactionPayload::KIND,
         }
     }
+
+    /// Returns the size of the payload once packed, including its kind, without packing it.
+    pub fn estimate_byte_size(&self) -> usize {
+        self.packed_len()
+    }
 }
 
 impl From<TransactionPayload> for Payload {
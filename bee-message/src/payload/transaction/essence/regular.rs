@@ -14,12 +14,15 @@ use bee_common::{
     packable::{Packable, Read, Write},
 };
 
+use crypto::hashes::{blake2b::Blake2b256, Digest};
+
 use alloc::{boxed::Box, vec::Vec};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RegularEssence {
     inputs: Box<[Input]>,
+    inputs_commitment: [u8; 32],
     outputs: Box<[Output]>,
     payload: Option<Payload>,
 }
@@ -42,6 +45,39 @@ impl RegularEssence {
     pub fn payload(&self) -> &Option<Payload> {
         &self.payload
     }
+
+    /// Returns the BLAKE2b-256 hash of the concatenated serialised inputs. It is part of the essence, so it is covered
+    /// by the signatures of the unlock blocks.
+    pub fn inputs_commitment(&self) -> [u8; 32] {
+        self.inputs_commitment
+    }
+
+    /// Checks that the amounts of the outputs are valid, and that they don't accumulate beyond the total supply of
+    /// tokens.
+    pub fn validate_outputs(&self) -> Result<(), Error> {
+        let mut total: u64 = 0;
+
+        for output in self.outputs.iter() {
+            let amount = match output {
+                Output::SignatureLockedSingle(single) => {
+                    single.validate()?;
+                    single.amount()
+                }
+                Output::SignatureLockedDustAllowance(dust_allowance) => dust_allowance.amount(),
+                _ => return Err(Error::InvalidOutputKind(output.kind())),
+            };
+
+            total = total
+                .checked_add(amount)
+                .ok_or_else(|| Error::InvalidAccumulatedOutput(total as u128 + amount as u128))?;
+
+            if total > IOTA_SUPPLY {
+                return Err(Error::InvalidAccumulatedOutput(total as u128));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Packable for RegularEssence {
@@ -50,6 +86,7 @@ impl Packable for RegularEssence {
     fn packed_len(&self) -> usize {
         0u16.packed_len()
             + self.inputs.iter().map(Packable::packed_len).sum::<usize>()
+            + self.inputs_commitment.len()
             + 0u16.packed_len()
             + self.outputs.iter().map(Packable::packed_len).sum::<usize>()
             + option_payload_packed_len(self.payload.as_ref())
@@ -60,6 +97,7 @@ impl Packable for RegularEssence {
         for input in self.inputs.iter() {
             input.pack(writer)?;
         }
+        writer.write_all(&self.inputs_commitment)?;
         (self.outputs.len() as u16).pack(writer)?;
         for output in self.outputs.iter() {
             output.pack(writer)?;
@@ -81,6 +119,9 @@ impl Packable for RegularEssence {
             inputs.push(Input::unpack(reader)?);
         }
 
+        let mut inputs_commitment = [0u8; 32];
+        reader.read_exact(&mut inputs_commitment)?;
+
         let outputs_len = u16::unpack(reader)? as usize;
 
         if !INPUT_OUTPUT_COUNT_RANGE.contains(&outputs_len) {
@@ -98,10 +139,26 @@ impl Packable for RegularEssence {
             builder = builder.with_payload(payload);
         }
 
-        builder.finish()
+        let essence = builder.finish()?;
+
+        if essence.inputs_commitment != inputs_commitment {
+            return Err(Error::InvalidInputsCommitment);
+        }
+
+        Ok(essence)
     }
 }
 
+fn inputs_commitment(inputs: &[Input]) -> [u8; 32] {
+    let mut hasher = Blake2b256::new();
+
+    for input in inputs {
+        hasher.update(input.pack_new());
+    }
+
+    hasher.finalize().into()
+}
+
 #[derive(Debug, Default)]
 pub struct RegularEssenceBuilder {
     pub(crate) inputs: Vec<Input>,
@@ -194,7 +251,7 @@ impl RegularEssenceBuilder {
 
                     total = total
                         .checked_add(single.amount())
-                        .ok_or_else(|| Error::InvalidAccumulatedOutput((total + single.amount()) as u128))?;
+                        .ok_or_else(|| Error::InvalidAccumulatedOutput(total as u128 + single.amount() as u128))?;
                 }
                 Output::SignatureLockedDustAllowance(dust_allowance) => {
                     // The address must be unique in the set of SignatureLockedDustAllowances.
@@ -210,9 +267,9 @@ impl RegularEssenceBuilder {
                         return Err(Error::DuplicateError);
                     }
 
-                    total = total
-                        .checked_add(dust_allowance.amount())
-                        .ok_or_else(|| Error::InvalidAccumulatedOutput((total + dust_allowance.amount()) as u128))?;
+                    total = total.checked_add(dust_allowance.amount()).ok_or_else(|| {
+                        Error::InvalidAccumulatedOutput(total as u128 + dust_allowance.amount() as u128)
+                    })?;
                 }
                 _ => return Err(Error::InvalidOutputKind(output.kind())),
             }
@@ -229,6 +286,7 @@ impl RegularEssenceBuilder {
         }
 
         Ok(RegularEssence {
+            inputs_commitment: inputs_commitment(&self.inputs),
             inputs: self.inputs.into_boxed_slice(),
             outputs: self.outputs.into_boxed_slice(),
             payload: self.payload,
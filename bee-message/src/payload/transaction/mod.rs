@@ -4,7 +4,13 @@
 mod essence;
 mod transaction_id;
 
-use crate::{unlock::UnlockBlocks, Error};
+use crate::{
+    address::Address,
+    constants::{DUST_ALLOWANCE_DIVISOR, DUST_OUTPUTS_MAX, DUST_THRESHOLD},
+    output::Output,
+    unlock::UnlockBlocks,
+    Error,
+};
 
 pub use essence::{Essence, RegularEssence, RegularEssenceBuilder};
 pub use transaction_id::{TransactionId, TRANSACTION_ID_LENGTH};
@@ -13,6 +19,9 @@ use bee_common::packable::{Packable, Read, Write};
 
 use crypto::hashes::{blake2b::Blake2b256, Digest};
 
+// TODO no_std
+use std::collections::HashMap;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransactionPayload {
@@ -43,6 +52,52 @@ impl TransactionPayload {
     pub fn unlock_blocks(&self) -> &UnlockBlocks {
         &self.unlock_blocks
     }
+
+    /// Checks that the dust outputs created by this transaction are allowed by the dust allowance outputs it creates
+    /// on the same addresses. Dust allowances already present in the ledger are not taken into account, see
+    /// `validate_dust_with` for that.
+    pub fn validate_dust(&self) -> Result<(), Error> {
+        self.validate_dust_with(|_| (0, 0))
+    }
+
+    /// Checks that the dust outputs created by this transaction are allowed like `validate_dust`, on top of the number
+    /// of dust outputs and the sum of dust allowances that `ledger` returns for each address, once the inputs of the
+    /// transaction are consumed.
+    pub fn validate_dust_with<F: Fn(&Address) -> (u64, u64)>(&self, ledger: F) -> Result<(), Error> {
+        let Essence::Regular(essence) = &self.essence;
+        // Number of dust outputs and sum of dust allowances per address.
+        let mut dust = HashMap::<Address, (u64, u64)>::new();
+
+        for output in essence.outputs() {
+            match output {
+                Output::SignatureLockedSingle(output) if output.amount() < DUST_THRESHOLD => {
+                    dust.entry(*output.address()).or_default().0 += 1;
+                }
+                Output::SignatureLockedDustAllowance(output) => {
+                    dust.entry(*output.address()).or_default().1 += output.amount();
+                }
+                _ => {}
+            }
+        }
+
+        for (address, (outputs, allowance)) in dust.iter().filter(|(_, (outputs, _))| *outputs > 0) {
+            let (ledger_outputs, ledger_allowance) = ledger(address);
+            let outputs = outputs + ledger_outputs;
+            let allowance = allowance + ledger_allowance;
+
+            if allowance == 0 {
+                return Err(Error::DustOutputWithoutAllowance);
+            }
+            if outputs > (allowance / DUST_ALLOWANCE_DIVISOR).min(DUST_OUTPUTS_MAX) {
+                return Err(Error::InsufficientDustAllowance(
+                    allowance,
+                    outputs * DUST_ALLOWANCE_DIVISOR,
+                ));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Packable for TransactionPayload {
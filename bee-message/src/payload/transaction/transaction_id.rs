@@ -1,7 +1,7 @@
 // Copyright 2020 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::Error;
+use crate::{constants::INPUT_OUTPUT_INDEX_RANGE, error::hex_decode_error, output::OutputId, Error};
 
 use bee_common::packable::{Packable, Read, Write};
 
@@ -16,11 +16,39 @@ impl TransactionId {
     pub fn new(bytes: [u8; TRANSACTION_ID_LENGTH]) -> Self {
         bytes.into()
     }
+
+    /// Returns all the output ids a transaction with this id can have.
+    pub fn outputs(&self) -> impl Iterator<Item = OutputId> {
+        let transaction_id = *self;
+
+        // Unwrap is fine because the index is within the valid range.
+        INPUT_OUTPUT_INDEX_RANGE.map(move |index| OutputId::new(transaction_id, index).unwrap())
+    }
 }
 
 #[cfg(feature = "serde")]
 string_serde_impl!(TransactionId);
 
+#[cfg(feature = "proptest")]
+impl proptest::arbitrary::Arbitrary for TransactionId {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use proptest::{arbitrary::any, strategy::Strategy};
+
+        any::<[u8; TRANSACTION_ID_LENGTH]>().prop_map(Self::new).boxed()
+    }
+}
+
+// `ZeroizeOnDrop` can't be provided as the type is `Copy` and therefore can't implement `Drop`.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for TransactionId {
+    fn zeroize(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
 impl From<[u8; TRANSACTION_ID_LENGTH]> for TransactionId {
     fn from(bytes: [u8; TRANSACTION_ID_LENGTH]) -> Self {
         Self(bytes)
@@ -32,7 +60,7 @@ impl FromStr for TransactionId {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let bytes: [u8; TRANSACTION_ID_LENGTH] = hex::decode(s)
-            .map_err(|_| Self::Err::InvalidHexadecimalChar(s.to_owned()))?
+            .map_err(|e| hex_decode_error(e, s, TRANSACTION_ID_LENGTH * 2))?
             .try_into()
             .map_err(|_| Self::Err::InvalidHexadecimalLength(TRANSACTION_ID_LENGTH * 2, s.len()))?;
 
@@ -10,6 +10,7 @@ pub use crate::{
         ConsumedOutput, CreatedOutput, Output, OutputId, SignatureLockedDustAllowanceOutput,
         SignatureLockedSingleOutput, TreasuryOutput, OUTPUT_ID_LENGTH,
     },
+    pack_framed,
     payload::{
         indexation::{HashedIndex, IndexationPayload, HASHED_INDEX_LENGTH},
         milestone::{
@@ -25,5 +26,6 @@ pub use crate::{
         Payload,
     },
     unlock::{Ed25519Signature, ReferenceUnlock, SignatureUnlock, UnlockBlock, UnlockBlocks},
-    Error, Message, MessageBuilder, MessageId, Parents, MESSAGE_ID_LENGTH, MESSAGE_LENGTH_MAX, MESSAGE_LENGTH_MIN,
+    unpack_framed, Error, Message, MessageBuilder, MessageId, Parents, MESSAGE_ID_LENGTH, MESSAGE_LENGTH_MAX,
+    MESSAGE_LENGTH_MIN,
 };
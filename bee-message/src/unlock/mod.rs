@@ -7,7 +7,7 @@ mod signature;
 pub use reference::ReferenceUnlock;
 pub use signature::{Ed25519Signature, SignatureUnlock};
 
-use crate::{constants::UNLOCK_BLOCK_COUNT_RANGE, Error};
+use crate::{constants::UNLOCK_BLOCK_COUNT_RANGE, output::SignatureLockedSingleOutput, Error};
 
 use bee_common::packable::{Packable, Read, Write};
 
@@ -122,6 +122,23 @@ impl UnlockBlocks {
             None => None,
         }
     }
+
+    /// Verifies that the unlock block at `index` unlocks `output`, the output consumed by the corresponding input, by
+    /// checking its signature - or the one it references - against the hash of the transaction essence. The essence
+    /// includes the commitment to its inputs, so the signature covers them too.
+    pub fn validate_against_input(
+        &self,
+        index: usize,
+        output: &SignatureLockedSingleOutput,
+        essence_hash: &[u8; 32],
+    ) -> Result<(), Error> {
+        match self.get(index) {
+            Some(UnlockBlock::Signature(signature)) => output.address().verify(essence_hash, signature),
+            // Already rejected by `new`, a reference can only point to a signature.
+            Some(UnlockBlock::Reference(_)) => Err(Error::InvalidUnlockBlockReference(index)),
+            None => Err(Error::MissingUnlockBlock(index)),
+        }
+    }
 }
 
 impl Deref for UnlockBlocks {
@@ -1,8 +1,11 @@
 // Copyright 2020 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use bee_common::packable::Packable;
 use bee_message::prelude::*;
 
+use bech32::{ToBase32, Variant};
+
 const ED25519_ADDRESS: &str = "52fdfc072182654f163f5f0f9a621d729566c74d10037c4d7bbb0407d1e2c649";
 
 #[test]
@@ -55,3 +58,43 @@ fn bech32_string_to_address() {
         panic!("Expecting an Ed25519 address");
     }
 }
+
+#[test]
+fn from_bech32_to_bech32() {
+    let mut bytes = [0; 32];
+    bytes.copy_from_slice(&hex::decode(ED25519_ADDRESS).unwrap());
+    let address = Address::from(Ed25519Address::new(bytes));
+
+    let (hrp, decoded) = Address::from_bech32(&address.to_bech32("atoi")).unwrap();
+
+    assert_eq!(hrp, "atoi");
+    assert_eq!(decoded, address);
+}
+
+#[test]
+fn from_bech32_invalid_checksum() {
+    assert!(matches!(
+        Address::from_bech32("iota1qpf0mlq8yxpx2nck8a0slxnzr4ef2ek8f5gqxlzd0wasgp73utryj430ldv"),
+        Err(Error::InvalidAddress)
+    ));
+}
+
+#[test]
+fn from_bech32_invalid_kind() {
+    assert!(matches!(
+        Address::from_bech32(&bech32::encode("iota", [1u8; 33].to_base32(), Variant::Bech32).unwrap()),
+        Err(Error::InvalidAddress)
+    ));
+}
+
+#[test]
+fn from_bech32_invalid_variant() {
+    let mut bytes = [0; 32];
+    bytes.copy_from_slice(&hex::decode(ED25519_ADDRESS).unwrap());
+    let address = Address::from(Ed25519Address::new(bytes));
+
+    assert!(matches!(
+        Address::from_bech32(&bech32::encode("iota", address.pack_new().to_base32(), Variant::Bech32m).unwrap()),
+        Err(Error::InvalidAddress)
+    ));
+}
@@ -0,0 +1,32 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "proptest")]
+
+use bee_common::packable::Packable;
+use bee_message::prelude::*;
+
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn transaction_id_pack_unpack(transaction_id in any::<TransactionId>()) {
+        let bytes = transaction_id.pack_new();
+
+        prop_assert_eq!(TransactionId::unpack(&mut bytes.as_slice()).unwrap(), transaction_id);
+    }
+
+    #[test]
+    fn output_id_pack_unpack(output_id in any::<OutputId>()) {
+        let bytes = output_id.pack_new();
+
+        prop_assert_eq!(OutputId::unpack(&mut bytes.as_slice()).unwrap(), output_id);
+    }
+
+    #[test]
+    fn utxo_input_pack_unpack(utxo_input in any::<UtxoInput>()) {
+        let bytes = utxo_input.pack_new();
+
+        prop_assert_eq!(UtxoInput::unpack(&mut bytes.as_slice()).unwrap(), utxo_input);
+    }
+}
@@ -55,6 +55,20 @@ fn new_invalid_data_length_more_than_max() {
     ));
 }
 
+#[test]
+fn tag_as_utf8_valid() {
+    let indexation = IndexationPayload::new(b"bee", &[]).unwrap();
+
+    assert_eq!(indexation.tag_as_utf8(), Some("bee"));
+}
+
+#[test]
+fn tag_as_utf8_invalid() {
+    let indexation = IndexationPayload::new(&[0xff, 0xfe], &[]).unwrap();
+
+    assert_eq!(indexation.tag_as_utf8(), None);
+}
+
 #[test]
 fn packed_len() {
     assert_eq!(
@@ -65,6 +79,15 @@ fn packed_len() {
     );
 }
 
+#[test]
+fn estimate_byte_size() {
+    let payload = Payload::from(
+        IndexationPayload::new(&rand_bytes(10), &[0x42, 0xff, 0x84, 0xa2, 0x42, 0xff, 0x84, 0xa2]).unwrap(),
+    );
+
+    assert_eq!(payload.estimate_byte_size(), payload.pack_new().len());
+}
+
 #[test]
 fn pack_unpack_valid() {
     let indexation_1 =
@@ -89,3 +89,73 @@ fn unpack_invalid_remaining_bytes() {
         Err(Error::RemainingBytesAfterMessage)
     ))
 }
+
+#[test]
+fn payload_kind_none() {
+    let message = MessageBuilder::new()
+        .with_network_id(0)
+        .with_parents(Parents::new(rand_message_ids(2)).unwrap())
+        .with_nonce_provider(ConstantBuilder::new().with_value(42).finish(), 0f64, None)
+        .finish()
+        .unwrap();
+
+    assert_eq!(message.payload_kind(), None);
+}
+
+#[test]
+fn payload_kind_indexation() {
+    let message = MessageBuilder::new()
+        .with_network_id(0)
+        .with_parents(Parents::new(rand_message_ids(2)).unwrap())
+        .with_nonce_provider(ConstantBuilder::new().with_value(42).finish(), 0f64, None)
+        .with_payload(IndexationPayload::new(&[42], &[0u8; 16]).unwrap().into())
+        .finish()
+        .unwrap();
+
+    assert_eq!(message.payload_kind(), Some(IndexationPayload::KIND));
+}
+
+#[test]
+fn hash_matches_id() {
+    let message = MessageBuilder::new()
+        .with_network_id(0)
+        .with_parents(Parents::new(rand_message_ids(2)).unwrap())
+        .with_nonce_provider(ConstantBuilder::new().with_value(42).finish(), 0f64, None)
+        .finish()
+        .unwrap();
+
+    assert_eq!(message.hash(), message.id().0);
+    assert_eq!(message.hash(), message.hash());
+    assert_eq!(
+        Message::unpack(&mut message.pack_new().as_slice()).unwrap().hash(),
+        message.id().0
+    );
+}
+
+#[test]
+fn pack_unpack_framed() {
+    let message = MessageBuilder::new()
+        .with_network_id(0)
+        .with_parents(Parents::new(rand_message_ids(2)).unwrap())
+        .with_nonce_provider(ConstantBuilder::new().with_value(42).finish(), 0f64, None)
+        .finish()
+        .unwrap();
+    let mut bytes = Vec::new();
+
+    pack_framed(&message, &mut bytes).unwrap();
+
+    assert_eq!(bytes.len(), 4 + message.packed_len());
+    assert_eq!(bytes[..4], (message.packed_len() as u32).to_le_bytes());
+    assert_eq!(unpack_framed(&mut bytes.as_slice()).unwrap().id(), message.id());
+}
+
+#[test]
+fn unpack_framed_invalid_length() {
+    let mut bytes = ((MESSAGE_LENGTH_MAX + 1) as u32).to_le_bytes().to_vec();
+    bytes.extend(vec![0u8; MESSAGE_LENGTH_MAX + 1]);
+
+    assert!(matches!(
+        unpack_framed(&mut bytes.as_slice()),
+        Err(Error::InvalidMessageLength(len)) if len == MESSAGE_LENGTH_MAX + 1
+    ));
+}
@@ -4,7 +4,7 @@
 use bee_common::packable::Packable;
 use bee_message::prelude::*;
 
-use core::str::FromStr;
+use core::{convert::TryInto, str::FromStr};
 
 const MESSAGE_ID: &str = "52fdfc072182654f163f5f0f9a621d729566c74d10037c4d7bbb0407d1e2c649";
 const MESSAGE_ID_INVALID_HEX: &str = "52fdfc072182654f163f5f0f9a621d729566c74d10037c4d7bbb0407d1e2c64x";
@@ -42,3 +42,30 @@ fn from_to_str() {
 fn packed_len() {
     assert_eq!(MessageId::from_str(MESSAGE_ID).unwrap().packed_len(), 32);
 }
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn zeroize() {
+    use zeroize::Zeroize;
+
+    let mut message_id = MessageId::from_str(MESSAGE_ID).unwrap();
+    message_id.zeroize();
+
+    assert_eq!(message_id, MessageId::null());
+}
+
+#[test]
+fn is_genesis() {
+    assert!(MessageId::GENESIS.is_genesis());
+    assert!(MessageId::null().is_genesis());
+    assert!(!MessageId::from_str(MESSAGE_ID).unwrap().is_genesis());
+}
+
+#[test]
+fn from_bytes_as_ref() {
+    let bytes: [u8; MESSAGE_ID_LENGTH] = hex::decode(MESSAGE_ID).unwrap().try_into().unwrap();
+    let message_id = MessageId::from(bytes);
+
+    assert_eq!(message_id, MessageId::from_str(MESSAGE_ID).unwrap());
+    assert_eq!(message_id.as_ref(), &bytes);
+}
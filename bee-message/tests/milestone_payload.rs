@@ -1,9 +1,11 @@
 // Copyright 2020 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use bee_message::prelude::*;
+use bee_message::{payload::milestone::MilestoneValidationError, prelude::*};
 use bee_test::rand::parents::rand_parents;
 
+use crypto::signatures::ed25519;
+
 #[test]
 fn kind() {
     assert_eq!(MilestonePayload::KIND, 1);
@@ -65,3 +67,79 @@ fn new_invalid_public_keys_sgnatures_count_mismatch() {
         Err(Error::MilestonePublicKeysSignaturesCountMismatch(2, 3))
     ));
 }
+
+#[test]
+fn verify_signatures_unapplicable_public_key() {
+    let milestone = MilestonePayload::new(
+        MilestonePayloadEssence::new(
+            MilestoneIndex(0),
+            0,
+            rand_parents(),
+            [0; MILESTONE_MERKLE_PROOF_LENGTH],
+            vec![[0; 32]],
+            None,
+        )
+        .unwrap(),
+        vec![Box::new([0u8; 64])],
+    )
+    .unwrap();
+
+    assert!(matches!(
+        milestone.verify_signatures(&[]),
+        Err(MilestoneValidationError::UnapplicablePublicKey(key)) if key == hex::encode([0u8; 32])
+    ));
+}
+
+fn signed_milestone(secret_key: &ed25519::SecretKey, signer: &ed25519::SecretKey) -> MilestonePayload {
+    let essence = MilestonePayloadEssence::new(
+        MilestoneIndex(0),
+        0,
+        rand_parents(),
+        [0; MILESTONE_MERKLE_PROOF_LENGTH],
+        vec![secret_key.public_key().to_compressed_bytes()],
+        None,
+    )
+    .unwrap();
+    let signature = signer.sign(&essence.hash()).to_bytes();
+
+    MilestonePayload::new(essence, vec![Box::new(signature)]).unwrap()
+}
+
+#[test]
+fn verify_signatures_valid() {
+    let secret_key = ed25519::SecretKey::from_le_bytes([1; 32]).unwrap();
+    let milestone = signed_milestone(&secret_key, &secret_key);
+
+    assert!(milestone.verify_signatures(&[secret_key.public_key()]).is_ok());
+    assert!(milestone
+        .validate(&[hex::encode(secret_key.public_key().to_compressed_bytes())], 1)
+        .is_ok());
+}
+
+#[test]
+fn verify_signatures_invalid() {
+    let secret_key = ed25519::SecretKey::from_le_bytes([1; 32]).unwrap();
+    let other_key = ed25519::SecretKey::from_le_bytes([2; 32]).unwrap();
+    let milestone = signed_milestone(&secret_key, &other_key);
+    let public_key = hex::encode(secret_key.public_key().to_compressed_bytes());
+
+    assert!(matches!(
+        milestone.verify_signatures(&[secret_key.public_key()]),
+        Err(MilestoneValidationError::InvalidSignature(0, key)) if key == public_key
+    ));
+    assert!(matches!(
+        milestone.validate(&[public_key.clone()], 1),
+        Err(MilestoneValidationError::InvalidSignature(0, key)) if key == public_key
+    ));
+}
+
+#[test]
+fn validate_unapplicable_public_key() {
+    let secret_key = ed25519::SecretKey::from_le_bytes([1; 32]).unwrap();
+    let milestone = signed_milestone(&secret_key, &secret_key);
+
+    assert!(matches!(
+        milestone.validate(&[hex::encode([0u8; 32])], 1),
+        Err(MilestoneValidationError::UnapplicablePublicKey(_))
+    ));
+}
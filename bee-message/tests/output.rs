@@ -0,0 +1,28 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_message::prelude::*;
+use bee_test::rand::address::rand_ed25519_address;
+
+#[test]
+fn amount_signature_locked_single() {
+    let output = Output::from(SignatureLockedSingleOutput::new(Address::from(rand_ed25519_address()), 42).unwrap());
+
+    assert_eq!(output.amount(), 42);
+}
+
+#[test]
+fn amount_signature_locked_dust_allowance() {
+    let address = Address::from(rand_ed25519_address());
+    let output = Output::from(SignatureLockedDustAllowanceOutput::new(address, 1_000_000).unwrap());
+
+    assert_eq!(output.amount(), 1_000_000);
+}
+
+#[test]
+fn amount_treasury() {
+    // Treasury outputs are not part of the UTXO ledger.
+    let output = Output::from(TreasuryOutput::new(1_000_000).unwrap());
+
+    assert_eq!(output.amount(), 0);
+}
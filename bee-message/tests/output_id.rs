@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use bee_common::packable::Packable;
-use bee_message::prelude::*;
+use bee_message::{constants::OUTPUT_INDEX_MAX, prelude::*};
 
 use core::{
     convert::{TryFrom, TryInto},
@@ -44,6 +44,29 @@ fn new_invalid() {
     ));
 }
 
+#[test]
+fn new_index_boundaries() {
+    let transaction_id = TransactionId::from_str(TRANSACTION_ID).unwrap();
+
+    assert_eq!(OutputId::new(transaction_id, 0).unwrap().index(), 0);
+    assert_eq!(
+        OutputId::new(transaction_id, OUTPUT_INDEX_MAX - 1).unwrap().index(),
+        OUTPUT_INDEX_MAX - 1
+    );
+    assert!(matches!(
+        OutputId::new(transaction_id, OUTPUT_INDEX_MAX),
+        Err(Error::InvalidInputOutputIndex(OUTPUT_INDEX_MAX))
+    ));
+    assert!(matches!(
+        OutputId::new(transaction_id, OUTPUT_INDEX_MAX + 1),
+        Err(Error::InvalidInputOutputIndex(index)) if index == OUTPUT_INDEX_MAX + 1
+    ));
+    assert!(matches!(
+        OutputId::new(transaction_id, u16::MAX),
+        Err(Error::InvalidInputOutputIndex(u16::MAX))
+    ));
+}
+
 #[test]
 fn try_from_valid() {
     let transaction_id = TransactionId::from_str(TRANSACTION_ID).unwrap();
@@ -64,6 +87,25 @@ fn try_from_invalid() {
     ));
 }
 
+#[test]
+fn from_bytes_to_bytes() {
+    let output_id_bytes: [u8; OUTPUT_ID_LENGTH] = hex::decode(OUTPUT_ID).unwrap().try_into().unwrap();
+    let output_id = OutputId::from_bytes(output_id_bytes).unwrap();
+
+    assert_eq!(output_id.to_bytes(), output_id_bytes);
+    assert_eq!(output_id.to_bytes().to_vec(), output_id.pack_new());
+}
+
+#[test]
+fn from_bytes_invalid() {
+    let output_id_bytes: [u8; OUTPUT_ID_LENGTH] = hex::decode(OUTPUT_ID_INVALID_INDEX).unwrap().try_into().unwrap();
+
+    assert!(matches!(
+        OutputId::from_bytes(output_id_bytes),
+        Err(Error::InvalidInputOutputIndex(127))
+    ));
+}
+
 #[test]
 fn from_str_valid() {
     let transaction_id = TransactionId::from_str(TRANSACTION_ID).unwrap();
@@ -99,6 +141,28 @@ fn from_str_invalid_len() {
     ));
 }
 
+#[test]
+fn from_str_invalid_odd_len() {
+    let hex = &OUTPUT_ID[..OUTPUT_ID.len() - 1];
+
+    assert!(matches!(
+        OutputId::from_str(hex),
+        Err(Error::InvalidHexadecimalLength(expected, actual))
+            if expected == OUTPUT_ID_LENGTH * 2 && actual == OUTPUT_ID_LENGTH * 2 - 1
+    ));
+}
+
+#[test]
+fn from_str_invalid_overlong() {
+    let hex = format!("{}00", OUTPUT_ID);
+
+    assert!(matches!(
+        OutputId::from_str(&hex),
+        Err(Error::InvalidHexadecimalLength(expected, actual))
+            if expected == OUTPUT_ID_LENGTH * 2 && actual == OUTPUT_ID_LENGTH * 2 + 2
+    ));
+}
+
 #[test]
 fn from_str_to_str() {
     let output_id = OutputId::from_str(OUTPUT_ID).unwrap();
@@ -131,3 +195,29 @@ fn pack_unpack_invalid() {
         Err(Error::InvalidInputOutputIndex(127))
     ));
 }
+
+#[test]
+fn display_invalid_index() {
+    assert_eq!(
+        OutputId::from_str(OUTPUT_ID_INVALID_INDEX).unwrap_err().to_string(),
+        "Invalid input or output index: 127."
+    );
+}
+
+#[test]
+fn display_invalid_len() {
+    assert_eq!(
+        OutputId::from_str(OUTPUT_ID_INVALID_LEN).unwrap_err().to_string(),
+        "Invalid hexadecimal length: expected 68, got 66."
+    );
+}
+
+#[test]
+fn boxed_error() {
+    fn parse(s: &str) -> Result<OutputId, Box<dyn std::error::Error>> {
+        Ok(OutputId::from_str(s)?)
+    }
+
+    assert!(parse(OUTPUT_ID).is_ok());
+    assert!(parse(OUTPUT_ID_INVALID_INDEX).is_err());
+}
@@ -5,6 +5,8 @@ use bee_common::packable::Packable;
 use bee_message::prelude::*;
 use bee_test::rand::message::{rand_message_id, rand_message_ids};
 
+use core::convert::TryFrom;
+
 #[test]
 fn new_valid() {
     let inner = rand_message_ids(8);
@@ -33,15 +35,48 @@ fn new_invalid_not_sorted() {
     let mut inner = rand_message_ids(8);
     inner.reverse();
 
-    assert!(matches!(Parents::new(inner), Err(Error::ParentsNotUniqueSorted)));
+    assert!(matches!(
+        Parents::new(inner.clone()),
+        Err(Error::ParentsNotSorted(previous, next)) if previous == inner[0] && next == inner[1]
+    ));
 }
 
 #[test]
 fn new_invalid_not_unique() {
     let mut inner = rand_message_ids(7);
-    inner.push(*inner.last().unwrap());
+    let last = *inner.last().unwrap();
+    inner.push(last);
+
+    assert!(matches!(Parents::new(inner), Err(Error::DuplicateParent(parent)) if parent == last));
+}
+
+#[test]
+fn try_from_invalid_less_than_min() {
+    assert!(matches!(
+        Parents::try_from(Vec::new()),
+        Err(Error::InvalidParentsCount(0))
+    ));
+}
 
-    assert!(matches!(Parents::new(inner), Err(Error::ParentsNotUniqueSorted)));
+#[test]
+fn from_unsorted_valid() {
+    let mut inner = rand_message_ids(7);
+    inner.push(inner[0]);
+    inner.reverse();
+
+    let parents = Parents::from_unsorted(inner).unwrap();
+    let parents_vec = parents.iter().copied().collect::<Vec<MessageId>>();
+
+    assert_eq!(parents.len(), 7);
+    assert!(parents_vec.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn from_unsorted_invalid_more_than_max() {
+    assert!(matches!(
+        Parents::from_unsorted(rand_message_ids(9)),
+        Err(Error::InvalidParentsCount(9))
+    ));
 }
 
 #[test]
@@ -97,7 +132,7 @@ fn unpack_invalid_not_sorted() {
 
     let parents = Parents::unpack(&mut packed.as_slice());
 
-    assert!(matches!(parents, Err(Error::ParentsNotUniqueSorted)));
+    assert!(matches!(parents, Err(Error::ParentsNotSorted(..))));
 }
 
 #[test]
@@ -112,5 +147,5 @@ fn upnack_invalid_not_unique() {
 
     let parents = Parents::unpack(&mut packed.as_slice());
 
-    assert!(matches!(parents, Err(Error::ParentsNotUniqueSorted)));
+    assert!(matches!(parents, Err(Error::DuplicateParent(..))));
 }
@@ -83,3 +83,13 @@ fn pack_unpack_invalid() {
         Err(Error::InvalidAmount(0))
     ));
 }
+
+#[test]
+fn validate_valid() {
+    let address = Address::from(Ed25519Address::from_str(ED25519_ADDRESS).unwrap());
+
+    assert!(SignatureLockedSingleOutput::new(address, IOTA_SUPPLY)
+        .unwrap()
+        .validate()
+        .is_ok());
+}
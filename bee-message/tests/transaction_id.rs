@@ -24,6 +24,28 @@ fn from_str_invalid_hex() {
     ));
 }
 
+#[test]
+fn from_str_invalid_odd_len() {
+    let hex = &TRANSACTION_ID[..TRANSACTION_ID.len() - 1];
+
+    assert!(matches!(
+        TransactionId::from_str(hex),
+        Err(Error::InvalidHexadecimalLength(expected, actual))
+            if expected == TRANSACTION_ID_LENGTH * 2 && actual == TRANSACTION_ID_LENGTH * 2 - 1
+    ));
+}
+
+#[test]
+fn from_str_invalid_overlong() {
+    let hex = format!("{}00", TRANSACTION_ID);
+
+    assert!(matches!(
+        TransactionId::from_str(&hex),
+        Err(Error::InvalidHexadecimalLength(expected, actual))
+            if expected == TRANSACTION_ID_LENGTH * 2 && actual == TRANSACTION_ID_LENGTH * 2 + 2
+    ));
+}
+
 #[test]
 fn from_str_invalid_len() {
     assert!(matches!(
@@ -45,3 +67,26 @@ fn from_to_str() {
 fn packed_len() {
     assert_eq!(TransactionId::from_str(TRANSACTION_ID).unwrap().packed_len(), 32);
 }
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn zeroize() {
+    use zeroize::Zeroize;
+
+    let mut transaction_id = TransactionId::from_str(TRANSACTION_ID).unwrap();
+    transaction_id.zeroize();
+
+    assert_eq!(transaction_id.as_ref(), &[0u8; TRANSACTION_ID_LENGTH]);
+}
+
+#[test]
+fn outputs() {
+    let transaction_id = TransactionId::from_str(TRANSACTION_ID).unwrap();
+    let output_ids = transaction_id.outputs().collect::<Vec<_>>();
+
+    assert_eq!(output_ids.len(), 127);
+    assert!(output_ids
+        .iter()
+        .all(|output_id| *output_id.transaction_id() == transaction_id));
+    assert!(output_ids.iter().map(OutputId::index).eq(0..127));
+}
@@ -1,9 +1,91 @@
 // Copyright 2020 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use bee_message::prelude::*;
+use bee_message::{constants::DUST_THRESHOLD, prelude::*};
+use bee_test::rand::{address::rand_ed25519_address, input::rand_input};
+
+fn transaction_payload(outputs: Vec<Output>) -> TransactionPayload {
+    TransactionPayload::builder()
+        .with_essence(
+            RegularEssence::builder()
+                .add_input(rand_input())
+                .with_outputs(outputs)
+                .finish()
+                .unwrap()
+                .into(),
+        )
+        .with_unlock_blocks(
+            UnlockBlocks::new(vec![SignatureUnlock::from(Ed25519Signature::new(
+                [0; 32],
+                Box::new([0; 64]),
+            ))
+            .into()])
+            .unwrap(),
+        )
+        .finish()
+        .unwrap()
+}
 
 #[test]
 fn kind() {
     assert_eq!(TransactionPayload::KIND, 0);
 }
+
+#[test]
+fn validate_dust_no_dust() {
+    let address = Address::from(rand_ed25519_address());
+    let output = SignatureLockedSingleOutput::new(address, DUST_THRESHOLD).unwrap();
+    let transaction = transaction_payload(vec![output.into()]);
+
+    assert!(transaction.validate_dust().is_ok());
+}
+
+#[test]
+fn validate_dust_with_allowance() {
+    let address = Address::from(rand_ed25519_address());
+    let transaction = transaction_payload(vec![
+        SignatureLockedSingleOutput::new(address, 1).unwrap().into(),
+        SignatureLockedDustAllowanceOutput::new(address, 1_000_000)
+            .unwrap()
+            .into(),
+    ]);
+
+    assert!(transaction.validate_dust().is_ok());
+}
+
+#[test]
+fn validate_dust_without_allowance() {
+    let address = Address::from(rand_ed25519_address());
+    let transaction = transaction_payload(vec![SignatureLockedSingleOutput::new(address, 1).unwrap().into()]);
+
+    assert!(matches!(
+        transaction.validate_dust(),
+        Err(Error::DustOutputWithoutAllowance)
+    ));
+}
+
+#[test]
+fn validate_dust_with_ledger_allowance() {
+    let address = Address::from(rand_ed25519_address());
+    let transaction = transaction_payload(vec![SignatureLockedSingleOutput::new(address, 1).unwrap().into()]);
+
+    // The allowance of the ledger covers 10 dust outputs, including the new one
+    assert!(transaction.validate_dust_with(|_| (9, 1_000_000)).is_ok());
+}
+
+#[test]
+fn validate_dust_insufficient_allowance() {
+    let address = Address::from(rand_ed25519_address());
+    let transaction = transaction_payload(vec![
+        SignatureLockedSingleOutput::new(address, 1).unwrap().into(),
+        SignatureLockedDustAllowanceOutput::new(address, 1_000_000)
+            .unwrap()
+            .into(),
+    ]);
+
+    // The allowance covers 10 dust outputs, and the ledger already holds 10 on the address
+    assert!(matches!(
+        transaction.validate_dust_with(|a| if *a == address { (10, 0) } else { (0, 0) }),
+        Err(Error::InsufficientDustAllowance(1_000_000, 1_100_000))
+    ));
+}
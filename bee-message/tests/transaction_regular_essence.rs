@@ -1,9 +1,89 @@
 // Copyright 2020 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use bee_common::packable::Packable;
 use bee_message::prelude::*;
+use bee_test::rand::{address::rand_ed25519_address, input::rand_input};
+
+use crypto::hashes::{blake2b::Blake2b256, Digest};
 
 #[test]
 fn kind() {
     assert_eq!(RegularEssence::KIND, 0);
 }
+
+#[test]
+fn finish_invalid_accumulated_output() {
+    let output_1 = SignatureLockedSingleOutput::new(Address::from(rand_ed25519_address()), IOTA_SUPPLY).unwrap();
+    let output_2 = SignatureLockedSingleOutput::new(Address::from(rand_ed25519_address()), IOTA_SUPPLY).unwrap();
+
+    assert!(matches!(
+        RegularEssence::builder()
+            .add_input(rand_input())
+            .add_output(output_1.into())
+            .add_output(output_2.into())
+            .finish(),
+        Err(Error::InvalidAccumulatedOutput(amount)) if amount == 2 * IOTA_SUPPLY as u128
+    ));
+}
+
+#[test]
+fn validate_outputs() {
+    let essence = RegularEssence::builder()
+        .add_input(rand_input())
+        .add_output(
+            SignatureLockedSingleOutput::new(Address::from(rand_ed25519_address()), IOTA_SUPPLY)
+                .unwrap()
+                .into(),
+        )
+        .finish()
+        .unwrap();
+
+    assert!(essence.validate_outputs().is_ok());
+}
+
+#[test]
+fn inputs_commitment() {
+    let input = rand_input();
+    let essence = RegularEssence::builder()
+        .add_input(input.clone())
+        .add_output(
+            SignatureLockedSingleOutput::new(Address::from(rand_ed25519_address()), 1_000_000)
+                .unwrap()
+                .into(),
+        )
+        .finish()
+        .unwrap();
+    let expected: [u8; 32] = Blake2b256::digest(&input.pack_new()).into();
+
+    assert_eq!(essence.inputs_commitment(), expected);
+}
+
+#[test]
+fn pack_unpack_inputs_commitment() {
+    let input = rand_input();
+    let essence = RegularEssence::builder()
+        .add_input(input.clone())
+        .add_output(
+            SignatureLockedSingleOutput::new(Address::from(rand_ed25519_address()), 1_000_000)
+                .unwrap()
+                .into(),
+        )
+        .finish()
+        .unwrap();
+    let mut bytes = essence.pack_new();
+
+    assert_eq!(
+        RegularEssence::unpack(&mut bytes.as_slice())
+            .unwrap()
+            .inputs_commitment(),
+        essence.inputs_commitment()
+    );
+
+    // The commitment directly follows the inputs
+    bytes[2 + input.packed_len()] ^= 1;
+    assert!(matches!(
+        RegularEssence::unpack(&mut bytes.as_slice()),
+        Err(Error::InvalidInputsCommitment)
+    ));
+}
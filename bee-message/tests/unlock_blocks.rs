@@ -85,3 +85,34 @@ fn new_valid() {
         .is_ok()
     );
 }
+
+#[test]
+fn validate_against_input_missing() {
+    let unlock_blocks = UnlockBlocks::new(vec![SignatureUnlock::from(Ed25519Signature::new(
+        [0; 32],
+        Box::new([0; 64]),
+    ))
+    .into()])
+    .unwrap();
+    let output = SignatureLockedSingleOutput::new(Address::from(Ed25519Address::new([0; 32])), 1_000_000).unwrap();
+
+    assert!(matches!(
+        unlock_blocks.validate_against_input(1, &output, &[0; 32]),
+        Err(Error::MissingUnlockBlock(1)),
+    ));
+}
+
+#[test]
+fn validate_against_input_reference_public_key_mismatch() {
+    let unlock_blocks = UnlockBlocks::new(vec![
+        SignatureUnlock::from(Ed25519Signature::new([0; 32], Box::new([0; 64]))).into(),
+        ReferenceUnlock::new(0).unwrap().into(),
+    ])
+    .unwrap();
+    let output = SignatureLockedSingleOutput::new(Address::from(Ed25519Address::new([0; 32])), 1_000_000).unwrap();
+
+    assert!(matches!(
+        unlock_blocks.validate_against_input(1, &output, &[0; 32]),
+        Err(Error::SignaturePublicKeyMismatch(_, _)),
+    ));
+}
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use bee_common::packable::Packable;
-use bee_message::prelude::*;
+use bee_message::{constants::OUTPUT_INDEX_MAX, prelude::*};
 
 use core::str::FromStr;
 
@@ -21,6 +21,16 @@ fn new_valid() {
     assert_eq!(*input.output_id(), output_id);
 }
 
+#[test]
+fn new_invalid_index() {
+    let output_id = OutputId::from_str(OUTPUT_ID).unwrap();
+
+    assert!(matches!(
+        UtxoInput::new(*output_id.transaction_id(), OUTPUT_INDEX_MAX),
+        Err(Error::InvalidInputOutputIndex(OUTPUT_INDEX_MAX))
+    ));
+}
+
 #[test]
 fn from_valid() {
     let output_id = OutputId::from_str(OUTPUT_ID).unwrap();
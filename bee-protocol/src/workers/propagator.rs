@@ -33,8 +33,8 @@ async fn propagate<B: StorageBackend>(
     let mut children = vec![message_id];
 
     'outer: while let Some(ref message_id) = children.pop() {
-        // Skip messages that are already solid.
-        if tangle.is_solid_message(message_id).await {
+        // Skip messages that are already solid, looking for the flag of the cache before the metadata.
+        if tangle.has_complete_past_cone(message_id).await || tangle.is_solid_message(message_id).await {
             continue 'outer;
         }
 
@@ -101,6 +101,9 @@ async fn propagate<B: StorageBackend>(
                 .await
                 .expect("Failed to fetch metadata.");
 
+            // The whole past cone of a solid message is available.
+            tangle.set_past_cone_complete(message_id).await;
+
             // Try to propagate as far as possible into the future.
             if let Some(msg_children) = tangle.get_children(&message_id).await {
                 for child in msg_children {
@@ -0,0 +1,23 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{error::Error, storage::*};
+
+use bee_message::{Message, MessageId};
+use bee_tangle::storage::Count;
+
+#[async_trait::async_trait]
+impl Count<MessageId, Message> for Storage {
+    async fn count(&self) -> Result<usize, <Self as StorageBackend>::Error> {
+        let cf = self
+            .inner
+            .cf_handle(CF_MESSAGE_ID_TO_MESSAGE)
+            .ok_or(Error::UnknownCf(CF_MESSAGE_ID_TO_MESSAGE))?;
+
+        // RocksDB only keeps an estimate of the number of keys of a column family
+        Ok(self
+            .inner
+            .property_int_value_cf(&cf, "rocksdb.estimate-num-keys")?
+            .unwrap_or_default() as usize)
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod batch;
+pub mod count;
 pub mod delete;
 pub mod exist;
 pub mod fetch;
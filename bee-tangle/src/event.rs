@@ -0,0 +1,102 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Event streams that can be observed on a tangle.
+//!
+//! Events are distributed through bounded broadcast channels so that a slow observer can never stall the tangle: the
+//! tangle never waits for observers and, once an observer falls behind by more than the channel capacity, the oldest
+//! events it has not yet seen are dropped. The observer is then told how many events it missed with a
+//! `StreamEvent::Lagged` item before receiving the next event.
+//!
+//! The `BoundedTangleNotifier` offers the same guarantee with a channel per subscriber instead: events that don't fit
+//! in a subscriber's channel are dropped for that subscriber only, and counted.
+
+use crate::sync::{broadcast, mpsc};
+
+use futures::{stream, Stream};
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Mutex,
+};
+
+/// The default capacity of tangle event channels.
+pub const DEFAULT_EVENT_CHANNEL_LEN: usize = 1024;
+
+/// An item of a tangle event stream.
+#[derive(Clone, Debug)]
+pub enum StreamEvent<E> {
+    /// An event emitted by the tangle.
+    Event(E),
+    /// The observer fell behind and this many of the oldest events were dropped.
+    Lagged(u64),
+}
+
+pub(crate) fn broadcast_stream<E: Clone>(rx: Option<broadcast::Receiver<E>>) -> impl Stream<Item = StreamEvent<E>> {
+    stream::unfold(rx, |rx| async move {
+        let mut rx = rx?;
+        match rx.recv().await {
+            Ok(event) => Some((StreamEvent::Event(event), Some(rx))),
+            Err(broadcast::error::RecvError::Lagged(n)) => Some((StreamEvent::Lagged(n), Some(rx))),
+            Err(broadcast::error::RecvError::Closed) => None,
+        }
+    })
+}
+
+pub(crate) fn broadcast<E>(tx: Option<&broadcast::Sender<E>>, event: impl FnOnce() -> E) {
+    if let Some(tx) = tx {
+        // An error only means that there are no observers at the moment
+        let _ = tx.send(event());
+    }
+}
+
+/// A notifier delivering events through a bounded channel per subscriber. Sending never blocks: when the channel of a
+/// subscriber is full, the event is dropped for that subscriber and counted in `dropped_events`.
+pub struct BoundedTangleNotifier<E> {
+    channel_capacity: usize,
+    subscribers: Mutex<Vec<mpsc::Sender<E>>>,
+    dropped_events: AtomicU64,
+}
+
+impl<E: Clone> BoundedTangleNotifier<E> {
+    /// Creates a notifier whose subscribers can each lag behind by up to `channel_capacity` events. A capacity of zero
+    /// is clamped to one.
+    pub fn new(channel_capacity: usize) -> Self {
+        Self {
+            channel_capacity: channel_capacity.max(1),
+            subscribers: Mutex::new(Vec::new()),
+            dropped_events: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns a stream of the events sent from now on.
+    pub fn subscribe(&self) -> impl Stream<Item = E> {
+        let (tx, rx) = mpsc::channel(self.channel_capacity);
+        // Unwrap is fine because the lock is never held across a panicking operation.
+        self.subscribers.lock().unwrap().push(tx);
+
+        stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|event| (event, rx)) })
+    }
+
+    /// Sends an event to all the subscribers, without waiting for any of them. Subscribers whose stream was dropped are
+    /// forgotten.
+    pub fn send(&self, event: E) {
+        // Unwrap is fine because the lock is never held across a panicking operation.
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|tx| match tx.try_send(event.clone()) {
+                Ok(()) => true,
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    self.dropped_events.fetch_add(1, Ordering::Relaxed);
+                    true
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => false,
+            });
+    }
+
+    /// Returns the number of events dropped because a subscriber's channel was full.
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped_events.load(Ordering::Relaxed)
+    }
+}
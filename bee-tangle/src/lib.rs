@@ -6,24 +6,34 @@
 // #![warn(missing_docs)]
 
 pub mod config;
+pub mod event;
 pub mod flags;
 pub mod metadata;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod ms_tangle;
+#[cfg(feature = "tower")]
+pub mod service;
 pub mod storage;
 pub mod traversal;
 pub mod unconfirmed_message;
 pub mod urts;
 pub mod vec_set;
+pub mod wal;
 pub mod worker;
 
 pub(crate) mod pruning;
 
+mod sync;
 mod tangle;
 mod vertex;
 
 pub use ms_tangle::MsTangle;
-pub use tangle::{Hooks, Tangle};
+pub use tangle::{
+    CycleError, Hooks, InMemoryTangle, InsertResult, NullHooks, ShardedTangle, Source, Tangle, TangleStats,
+};
 pub use urts::BELOW_MAX_DEPTH;
+pub use vertex::VertexSnapshot;
 pub use worker::TangleWorker;
 
 use crate::vec_set::VecSet;
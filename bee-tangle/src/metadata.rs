@@ -126,6 +126,22 @@ impl MessageMetadata {
     }
 }
 
+// Not derived, as `IndexId` only compares the indexes while any change of the metadata has to bump its version.
+impl PartialEq for MessageMetadata {
+    fn eq(&self, other: &Self) -> bool {
+        let index_id = |index_id: &Option<IndexId>| index_id.map(|i| (i.index(), i.id()));
+
+        self.flags == other.flags
+            && self.milestone_index == other.milestone_index
+            && self.arrival_timestamp == other.arrival_timestamp
+            && self.solidification_timestamp == other.solidification_timestamp
+            && self.reference_timestamp == other.reference_timestamp
+            && index_id(&self.omrsi) == index_id(&other.omrsi)
+            && index_id(&self.ymrsi) == index_id(&other.ymrsi)
+            && self.conflict == other.conflict
+    }
+}
+
 #[derive(Debug)]
 pub enum MessageMetadataError {
     Io(std::io::Error),
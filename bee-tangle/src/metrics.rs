@@ -0,0 +1,74 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Prometheus metrics describing the health of a tangle.
+
+use prometheus::{Counter, Histogram, HistogramOpts, IntGauge, Registry};
+
+/// A set of prometheus metrics updated by a tangle.
+#[derive(Clone)]
+pub struct TangleMetrics {
+    /// The number of vertices in the cache.
+    pub cache_size: IntGauge,
+    /// The number of messages inserted.
+    pub inserts: Counter,
+    /// The number of vertices evicted from the cache.
+    pub evictions: Counter,
+    /// The number of messages successfully fetched from the hooks.
+    pub hook_hits: Counter,
+    /// The number of messages unsuccessfully fetched from the hooks.
+    pub hook_misses: Counter,
+    /// The time taken by insertions, in seconds.
+    pub insert_latency: Histogram,
+    /// The number of tips older than the threshold of the last orphan count.
+    pub orphans: IntGauge,
+    /// The time spent waiting to read the vertices, in seconds.
+    pub read_wait_time: Histogram,
+    /// The time spent waiting to write the vertices, in seconds.
+    pub write_wait_time: Histogram,
+    /// The number of tasks currently reading the vertices.
+    pub concurrent_readers: IntGauge,
+}
+
+impl TangleMetrics {
+    /// Creates the tangle metrics and registers them with the given registry.
+    pub fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let metrics = Self {
+            cache_size: IntGauge::new("tangle_cache_size", "Number of vertices in the tangle cache")?,
+            inserts: Counter::new("tangle_inserts", "Number of messages inserted into the tangle")?,
+            evictions: Counter::new("tangle_evictions", "Number of vertices evicted from the tangle cache")?,
+            hook_hits: Counter::new("tangle_hook_hits", "Number of messages fetched from the tangle hooks")?,
+            hook_misses: Counter::new("tangle_hook_misses", "Number of messages missing from the tangle hooks")?,
+            insert_latency: Histogram::with_opts(HistogramOpts::new(
+                "tangle_insert_latency",
+                "Time taken by tangle insertions, in seconds",
+            ))?,
+            orphans: IntGauge::new("tangle_orphans", "Number of tips older than the orphan threshold")?,
+            read_wait_time: Histogram::with_opts(HistogramOpts::new(
+                "tangle_read_wait_time",
+                "Time spent waiting to read the tangle vertices, in seconds",
+            ))?,
+            write_wait_time: Histogram::with_opts(HistogramOpts::new(
+                "tangle_write_wait_time",
+                "Time spent waiting to write the tangle vertices, in seconds",
+            ))?,
+            concurrent_readers: IntGauge::new(
+                "tangle_concurrent_readers",
+                "Number of tasks currently reading the tangle vertices",
+            )?,
+        };
+
+        registry.register(Box::new(metrics.cache_size.clone()))?;
+        registry.register(Box::new(metrics.inserts.clone()))?;
+        registry.register(Box::new(metrics.evictions.clone()))?;
+        registry.register(Box::new(metrics.hook_hits.clone()))?;
+        registry.register(Box::new(metrics.hook_misses.clone()))?;
+        registry.register(Box::new(metrics.insert_latency.clone()))?;
+        registry.register(Box::new(metrics.orphans.clone()))?;
+        registry.register(Box::new(metrics.read_wait_time.clone()))?;
+        registry.register(Box::new(metrics.write_wait_time.clone()))?;
+        registry.register(Box::new(metrics.concurrent_readers.clone()))?;
+
+        Ok(metrics)
+    }
+}
@@ -3,7 +3,8 @@
 
 use crate::{
     metadata::{IndexId, MessageMetadata},
-    storage::StorageBackend,
+    storage::{Count, StorageBackend},
+    sync::Mutex,
     tangle::{Hooks, Tangle, DEFAULT_CACHE_LEN},
     urts::UrtsTipPool,
     MessageRef,
@@ -20,11 +21,12 @@ use async_trait::async_trait;
 use hashbrown::HashMap;
 use log::{info, trace};
 use ref_cast::RefCast;
-use tokio::sync::Mutex;
 
 use std::{
+    io::{self, Write},
     ops::Deref,
     sync::atomic::{AtomicU32, Ordering},
+    time::{Duration, Instant},
 };
 
 pub struct StorageHooks<B> {
@@ -66,6 +68,11 @@ impl<B: StorageBackend> Hooks<MessageMetadata> for StorageHooks<B> {
         }
         Ok(())
     }
+
+    async fn count(&self) -> Result<usize, Self::Error> {
+        trace!("Attempted to count messages");
+        Count::<MessageId, Message>::count(&*self.storage).await
+    }
 }
 
 impl<B: StorageBackend> StorageHooks<B> {
@@ -342,6 +349,24 @@ impl<B: StorageBackend> MsTangle<B> {
         }
     }
 
+    /// Writes a Graphviz `digraph` of the messages currently held in the cache, for debugging purposes. Solid messages
+    /// are filled in green and the tips of the tip pool are outlined in bold. Edges go from parents to children.
+    pub async fn export_graphviz(&self, writer: impl Write) -> io::Result<()> {
+        // Copied so that the tip pool is not locked for the whole export
+        let tips = self.tip_pool.lock().await.non_lazy_tips().clone();
+
+        self.inner
+            .write_graphviz(writer, |metadata| metadata.flags().is_solid(), |id| tips.contains(id))
+            .await
+    }
+
+    /// Returns the index of the milestone that referenced the message associated with `id`, if any.
+    pub async fn get_referenced_milestone(&self, id: &MessageId) -> Option<MilestoneIndex> {
+        self.get_metadata(id)
+            .await
+            .and_then(|metadata| metadata.milestone_index())
+    }
+
     pub async fn omrsi(&self, id: &MessageId) -> Option<IndexId> {
         match self.solid_entry_points.lock().await.get(SolidEntryPoint::ref_cast(id)) {
             Some(sep) => Some(IndexId::new(*sep, *id)),
@@ -381,6 +406,23 @@ impl<B: StorageBackend> MsTangle<B> {
     pub async fn non_lazy_tips_num(&self) -> usize {
         self.tip_pool.lock().await.non_lazy_tips().len()
     }
+
+    /// Returns the most recently inserted tip of the tip pool, along with the time it was inserted at.
+    pub async fn get_youngest_tip(&self) -> Option<(MessageId, Instant)> {
+        self.tip_pool.lock().await.youngest_tip()
+    }
+
+    /// Returns the number of tips that have been in the tip pool for longer than `max_age`.
+    pub async fn count_orphans(&self, max_age: Duration) -> usize {
+        let orphans = self.tip_pool.lock().await.count_orphans(max_age);
+
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics() {
+            metrics.orphans.set(orphans as i64);
+        }
+
+        orphans
+    }
 }
 
 // #[cfg(test)]
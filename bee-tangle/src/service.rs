@@ -0,0 +1,114 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A `tower::Service` implementation for the tangle, allowing it to be composed with tower middleware.
+
+use crate::{tangle::Hooks, MessageRef, Tangle};
+
+use bee_message::{Message, MessageId};
+
+use futures::future::BoxFuture;
+use tower::Service;
+
+use std::{
+    convert::Infallible,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+/// A request that can be made to a tangle through its `tower::Service` implementation.
+pub enum TangleRequest<T> {
+    /// Get the message associated with the given `MessageId`.
+    Get(MessageId),
+    /// Get the metadata associated with the given `MessageId`.
+    GetMetadata(MessageId),
+    /// Get the children of the given `MessageId`.
+    GetChildren(MessageId),
+    /// Check whether the given `MessageId` is stored in the tangle.
+    Contains(MessageId),
+    /// Insert a message with its metadata.
+    Insert(MessageId, Message, T),
+}
+
+/// A response to a `TangleRequest`.
+pub enum TangleResponse<T> {
+    /// The response to a `TangleRequest::Get`.
+    Message(Option<MessageRef>),
+    /// The response to a `TangleRequest::GetMetadata`.
+    Metadata(Option<T>),
+    /// The response to a `TangleRequest::GetChildren`.
+    Children(Option<Vec<MessageId>>),
+    /// The response to a `TangleRequest::Contains`.
+    Contains(bool),
+    /// The response to a `TangleRequest::Insert`.
+    Inserted(Option<MessageRef>),
+}
+
+/// A `tower::Service` sharing a tangle, so that it can be cloned and its futures outlive it, as middleware expects.
+pub struct TangleService<T, H>
+where
+    T: Clone,
+{
+    tangle: Arc<Tangle<T, H>>,
+}
+
+impl<T, H> TangleService<T, H>
+where
+    T: Clone,
+{
+    /// Creates a new service for the given tangle.
+    pub fn new(tangle: Arc<Tangle<T, H>>) -> Self {
+        Self { tangle }
+    }
+
+    /// Returns the tangle of the service.
+    pub fn tangle(&self) -> &Arc<Tangle<T, H>> {
+        &self.tangle
+    }
+}
+
+impl<T, H> Clone for TangleService<T, H>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            tangle: self.tangle.clone(),
+        }
+    }
+}
+
+impl<T, H> Service<TangleRequest<T>> for TangleService<T, H>
+where
+    T: Clone + Send + Sync + 'static,
+    H: Hooks<T> + Send + Sync + 'static,
+{
+    type Response = TangleResponse<T>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // The tangle is always ready to accept requests.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: TangleRequest<T>) -> Self::Future {
+        let tangle = self.tangle.clone();
+
+        Box::pin(async move {
+            Ok(match request {
+                TangleRequest::Get(message_id) => TangleResponse::Message(tangle.get(&message_id).await),
+                TangleRequest::GetMetadata(message_id) => {
+                    TangleResponse::Metadata(tangle.get_metadata(&message_id).await)
+                }
+                TangleRequest::GetChildren(message_id) => {
+                    TangleResponse::Children(tangle.get_children(&message_id).await)
+                }
+                TangleRequest::Contains(message_id) => TangleResponse::Contains(tangle.contains(&message_id).await),
+                TangleRequest::Insert(message_id, message, metadata) => {
+                    TangleResponse::Inserted(tangle.insert(message_id, message, metadata).await)
+                }
+            })
+        })
+    }
+}
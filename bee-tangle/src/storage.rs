@@ -13,6 +13,15 @@ use bee_storage::{
     backend,
 };
 
+use async_trait::async_trait;
+
+/// Counts the values of a given kind held by a storage backend.
+#[async_trait]
+pub trait Count<K, V>: backend::StorageBackend {
+    /// Returns the number of values held by the storage, which may be an estimate.
+    async fn count(&self) -> Result<usize, Self::Error>;
+}
+
 pub trait StorageBackend:
     backend::StorageBackend
     + Insert<MessageId, Message>
@@ -23,6 +32,7 @@ pub trait StorageBackend:
     + Fetch<MessageId, MessageMetadata>
     + Fetch<MessageId, Vec<MessageId>>
     + Fetch<MilestoneIndex, Milestone>
+    + Count<MessageId, Message>
     + SnapshotStorageBackend
 {
 }
@@ -37,6 +47,7 @@ impl<T> StorageBackend for T where
         + Fetch<MessageId, MessageMetadata>
         + Fetch<MessageId, Vec<MessageId>>
         + Fetch<MilestoneIndex, Milestone>
+        + Count<MessageId, Message>
         + SnapshotStorageBackend
 {
 }
@@ -0,0 +1,132 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! The asynchronous synchronisation primitives, timers and scheduling helpers used by the tangle.
+//!
+//! This is the only place where the tangle depends on the `tokio` runtime for synchronisation and scheduling:
+//! supporting another runtime only requires providing equivalents of the primitives exported here. The `TangleWorker`
+//! is the exception, as it is driven by the node runtime.
+
+#[cfg(feature = "metrics")]
+use crate::metrics::TangleMetrics;
+
+#[cfg(feature = "metrics")]
+use prometheus::IntGauge;
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+pub(crate) use tokio::{
+    sync::{broadcast, mpsc, Mutex, Notify},
+    task::{spawn, spawn_blocking, yield_now},
+    time::{error::Elapsed, timeout},
+};
+
+use std::ops::Deref;
+
+/// An asynchronous reader-writer lock recording, when metrics are enabled, how long tasks wait to acquire it and how
+/// many readers hold it at once.
+pub(crate) struct InstrumentedRwLock<T> {
+    inner: RwLock<T>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<TangleMetrics>,
+}
+
+impl<T> InstrumentedRwLock<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Self {
+            inner: RwLock::new(value),
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    pub(crate) fn with_metrics(value: T, metrics: TangleMetrics) -> Self {
+        Self {
+            inner: RwLock::new(value),
+            metrics: Some(metrics),
+        }
+    }
+
+    pub(crate) async fn read(&self) -> InstrumentedReadGuard<'_, T> {
+        #[cfg(feature = "metrics")]
+        let timer = self.metrics.as_ref().map(|m| m.read_wait_time.start_timer());
+
+        let guard = self.inner.read().await;
+
+        #[cfg(feature = "metrics")]
+        let readers = self.metrics.as_ref().map(|m| {
+            m.concurrent_readers.inc();
+            m.concurrent_readers.clone()
+        });
+        #[cfg(feature = "metrics")]
+        if let Some(timer) = timer {
+            timer.observe_duration();
+        }
+
+        InstrumentedReadGuard {
+            guard,
+            reader: ReaderToken {
+                #[cfg(feature = "metrics")]
+                readers,
+            },
+        }
+    }
+
+    pub(crate) async fn write(&self) -> RwLockWriteGuard<'_, T> {
+        #[cfg(feature = "metrics")]
+        let timer = self.metrics.as_ref().map(|m| m.write_wait_time.start_timer());
+
+        let guard = self.inner.write().await;
+
+        #[cfg(feature = "metrics")]
+        if let Some(timer) = timer {
+            timer.observe_duration();
+        }
+
+        guard
+    }
+}
+
+// Kept apart from the guard itself so that the guard can still be destructured when mapped.
+struct ReaderToken {
+    #[cfg(feature = "metrics")]
+    readers: Option<IntGauge>,
+}
+
+#[cfg(feature = "metrics")]
+impl Drop for ReaderToken {
+    fn drop(&mut self) {
+        if let Some(readers) = &self.readers {
+            readers.dec();
+        }
+    }
+}
+
+/// A read guard of an `InstrumentedRwLock`.
+pub(crate) struct InstrumentedReadGuard<'a, T: ?Sized> {
+    guard: RwLockReadGuard<'a, T>,
+    reader: ReaderToken,
+}
+
+impl<'a, T: ?Sized> InstrumentedReadGuard<'a, T> {
+    /// Attempts to make a new guard for a component of the locked data, returning the original guard on failure.
+    pub(crate) fn try_map<U: ?Sized, F: FnOnce(&T) -> Option<&U>>(
+        this: Self,
+        f: F,
+    ) -> Result<InstrumentedReadGuard<'a, U>, Self> {
+        let Self { guard, reader } = this;
+
+        match RwLockReadGuard::try_map(guard, f) {
+            Ok(guard) => Ok(InstrumentedReadGuard { guard, reader }),
+            Err(guard) => Err(Self { guard, reader }),
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for InstrumentedReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
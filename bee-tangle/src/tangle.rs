@@ -1,27 +1,93 @@
 // Copyright 2020 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{vertex::Vertex, MessageRef};
+#[cfg(feature = "metrics")]
+use crate::metrics::TangleMetrics;
+use crate::{
+    event::{self, StreamEvent},
+    sync::{self, broadcast, Elapsed, InstrumentedReadGuard, InstrumentedRwLock, Mutex, Notify},
+    vertex::{Vertex, VertexSnapshot},
+    MessageRef,
+};
 
+use bee_common::packable::Packable;
 use bee_message::{Message, MessageId};
 
 use async_trait::async_trait;
+use futures::{future::join_all, stream, Stream, StreamExt};
 // use dashmap::{mapref::entry::Entry, DashMap};
 use hashbrown::{hash_map::DefaultHashBuilder, HashMap};
 use log::info;
 use lru::LruCache;
-use tokio::sync::{Mutex, RwLock as TRwLock, RwLockReadGuard as TRwLockReadGuard};
+use serde::Serialize;
 
 use std::{
+    collections::{HashSet, VecDeque},
     fmt::Debug,
+    io::{self, Write},
     marker::PhantomData,
     ops::Deref,
-    sync::atomic::{AtomicUsize, Ordering},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 
 pub const DEFAULT_CACHE_LEN: usize = 100_000;
+/// The default number of vertices visited by long traversals and scans between two yields to the scheduler.
+pub const DEFAULT_YIELD_INTERVAL: usize = 1024;
 const CACHE_THRESHOLD_FACTOR: f64 = 0.1;
 
+/// The outcome of inserting a message into a tangle.
+pub enum InsertResult {
+    /// The message was inserted.
+    Inserted(MessageRef),
+    /// The message was already present, and is left untouched.
+    AlreadyPresent(MessageRef),
+    /// The message was rejected by the tangle's filters, and was not inserted.
+    Rejected,
+    /// The cache reached the hard cap set `with_hard_cap` and eviction could not make room, so the message was not
+    /// inserted.
+    CacheFull,
+}
+
+/// Where the result of a lookup in a tangle came from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Source {
+    /// The message was found in the cache.
+    Cache,
+    /// The message was fetched from the hooks.
+    Backend,
+    /// The message was found neither in the cache nor in the hooks.
+    Missing,
+}
+
+/// A snapshot of the state of a tangle.
+#[derive(Clone, Debug, Serialize)]
+pub struct TangleStats {
+    /// The number of vertices in the cache.
+    pub vertices: usize,
+    /// The number of vertices in the cache that hold a message.
+    pub messages: usize,
+    /// The maximum number of vertices in the cache.
+    pub capacity: usize,
+    /// The number of vertices the cache is shrunk to when it exceeds its capacity.
+    pub eviction_threshold: usize,
+    /// The number of messages inserted, if the tangle has metrics.
+    pub inserts: Option<u64>,
+    /// The number of vertices evicted from the cache, if the tangle has metrics.
+    pub evictions: Option<u64>,
+    /// The number of messages successfully fetched from the hooks, if the tangle has metrics.
+    pub hook_hits: Option<u64>,
+    /// The number of messages unsuccessfully fetched from the hooks, if the tangle has metrics.
+    pub hook_misses: Option<u64>,
+}
+
+/// An error raised when a cycle is found while traversing a tangle, containing the message closing the cycle.
+#[derive(Debug)]
+pub struct CycleError(pub MessageId);
+
 /// A trait used to provide hooks for a tangle. The tangle acts as an in-memory cache and will use hooks to extend its
 /// effective volume. When an entry doesn't exist in the tangle cache and needs fetching, or when an entry gets
 /// inserted, the tangle will call out to the hooks in order to fulfil these actions.
@@ -30,16 +96,39 @@ pub trait Hooks<T> {
     /// An error generated by these hooks.
     type Error: Debug;
 
+    /// Whether these hooks do nothing, in which case the tangle skips calling them altogether. Hooks wrapping other
+    /// hooks should forward it.
+    fn is_noop(&self) -> bool {
+        false
+    }
+
     /// Fetch a message from some external storage medium.
     async fn get(&self, message_id: &MessageId) -> Result<Option<(Message, T)>, Self::Error>;
     /// Insert a message into some external storage medium.
     async fn insert(&self, message_id: MessageId, tx: Message, metadata: T) -> Result<(), Self::Error>;
     /// Fetch the approvers list for a given message.
     async fn fetch_approvers(&self, message_id: &MessageId) -> Result<Option<Vec<MessageId>>, Self::Error>;
+    /// Fetch the approvers lists for several messages, in the same order. Backends able to fetch several entries at
+    /// once should override the default implementation, which fetches them one by one.
+    async fn fetch_approvers_batch(
+        &self,
+        message_ids: &[MessageId],
+    ) -> Result<Vec<Option<Vec<MessageId>>>, Self::Error> {
+        let mut approvers = Vec::with_capacity(message_ids.len());
+        for message_id in message_ids {
+            approvers.push(self.fetch_approvers(message_id).await?);
+        }
+        Ok(approvers)
+    }
     /// Insert a new approver for a given message.
     async fn insert_approver(&self, message_id: MessageId, approver: MessageId) -> Result<(), Self::Error>;
     /// Update the approvers list for a given message.
     async fn update_approvers(&self, message_id: MessageId, approvers: &[MessageId]) -> Result<(), Self::Error>;
+    /// Count the messages stored in some external storage medium. Backends unable to count their messages may keep the
+    /// default implementation, which reports none.
+    async fn count(&self) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
 }
 
 /// Phoney default hooks that do nothing.
@@ -55,6 +144,10 @@ impl<T> Default for NullHooks<T> {
 impl<T: Send + Sync> Hooks<T> for NullHooks<T> {
     type Error = ();
 
+    fn is_noop(&self) -> bool {
+        true
+    }
+
     async fn get(&self, _message_id: &MessageId) -> Result<Option<(Message, T)>, Self::Error> {
         Ok(None)
     }
@@ -76,16 +169,73 @@ impl<T: Send + Sync> Hooks<T> for NullHooks<T> {
     }
 }
 
+// An insertion in flight, that `freeze` waits for until dropped.
+struct InFlight<'a> {
+    in_flight: &'a AtomicUsize,
+    drained: &'a Notify,
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        if self.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.drained.notify_waiters();
+        }
+    }
+}
+
+/// A purely in-memory tangle, without any hooks. Calls to the hooks are skipped entirely, rather than dispatched to
+/// hooks doing nothing.
+pub type InMemoryTangle<T> = Tangle<T, NullHooks<T>>;
+
+/// A tangle whose vertices are split across several locks to reduce contention, created `with_shards`.
+pub type ShardedTangle<T, H = NullHooks<T>> = Tangle<T, H>;
+
+type OnInsert<T> = Box<dyn Fn(&MessageId, &mut Vertex<T>) + Send + Sync>;
+
+// Slots of the cache reserved by an insertion in flight, counted along with the vertices until dropped.
+struct Reservation<'a> {
+    cache_len: &'a AtomicUsize,
+    slots: usize,
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        self.cache_len.fetch_sub(self.slots, Ordering::Relaxed);
+    }
+}
+
 /// A foundational, thread-safe graph datastructure to represent the IOTA Tangle.
+///
+/// Locks are released when the futures holding them are dropped, and the cache is only changed once all the locks a
+/// change needs are held, so it is never left half-updated. Writes to the hooks happen after the cache is updated
+/// though, so whether dropping a future part-way through is safe is documented for each method.
 pub struct Tangle<T, H = NullHooks<T>>
 where
     T: Clone,
 {
-    // Global Tangle Lock. Remove this as and when it is deemed correct to do so.
-    vertices: TRwLock<HashMap<MessageId, Vertex<T>>>,
+    // Global Tangle Lock, unless sharded. Remove this as and when it is deemed correct to do so.
+    vertices: Box<[InstrumentedRwLock<HashMap<MessageId, Vertex<T>>>]>,
 
     pub(crate) cache_queue: Mutex<LruCache<MessageId, (), DefaultHashBuilder>>,
+    // Number of vertices across all the shards, plus the slots reserved by insertions in flight under a hard cap
+    cache_len: AtomicUsize,
     max_len: AtomicUsize,
+    hard_cap: Option<usize>,
+    network_id: Option<u64>,
+    max_message_size: Option<usize>,
+    yield_interval: usize,
+
+    frozen: AtomicBool,
+    unfrozen: Notify,
+    in_flight: AtomicUsize,
+    drained: Notify,
+
+    metadata_changed: Option<broadcast::Sender<(MessageId, T)>>,
+    dirty: Option<Mutex<HashMap<MessageId, (Message, T)>>>,
+    on_insert: Option<OnInsert<T>>,
+
+    #[cfg(feature = "metrics")]
+    metrics: Option<TangleMetrics>,
 
     pub(crate) hooks: H,
 }
@@ -107,26 +257,159 @@ where
     /// Creates a new Tangle.
     pub fn new(hooks: H) -> Self {
         Self {
-            vertices: TRwLock::new(HashMap::new()),
+            vertices: vec![InstrumentedRwLock::new(HashMap::new())].into_boxed_slice(),
 
+            // Left unbounded: concurrent insertions can overshoot the capacity before eviction trims the queue along
+            // with the vertices, and a bounded queue would then drop entries whose vertices could never be evicted.
             cache_queue: Mutex::new(LruCache::unbounded_with_hasher(DefaultHashBuilder::default())),
+            cache_len: AtomicUsize::new(0),
             max_len: AtomicUsize::new(DEFAULT_CACHE_LEN),
+            hard_cap: None,
+            network_id: None,
+            max_message_size: None,
+            yield_interval: DEFAULT_YIELD_INTERVAL,
+
+            frozen: AtomicBool::new(false),
+            unfrozen: Notify::new(),
+            in_flight: AtomicUsize::new(0),
+            drained: Notify::new(),
+
+            metadata_changed: None,
+            dirty: None,
+            on_insert: None,
+
+            #[cfg(feature = "metrics")]
+            metrics: None,
 
             hooks,
         }
     }
 
-    /// Create a new tangle with the given capacity.
+    /// Creates a new Tangle, registering its metrics with the given registry.
+    #[cfg(feature = "metrics")]
+    pub fn new_with_metrics(hooks: H, registry: &prometheus::Registry) -> Result<Self, prometheus::Error> {
+        let metrics = TangleMetrics::new(registry)?;
+
+        Ok(Self {
+            vertices: vec![InstrumentedRwLock::with_metrics(HashMap::new(), metrics.clone())].into_boxed_slice(),
+            metrics: Some(metrics),
+            ..Self::new(hooks)
+        })
+    }
+
+    /// Return the metrics of this tangle, if it was created with them.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> Option<&TangleMetrics> {
+        self.metrics.as_ref()
+    }
+
+    /// Create a new tangle with the given capacity, overriding `DEFAULT_CACHE_LEN`. A capacity of zero is clamped to
+    /// one, since an empty cache would evict everything on every insertion.
     pub fn with_capacity(self, cap: usize) -> Self {
+        let cap = cap.max(1);
+
+        Self {
+            max_len: AtomicUsize::new(cap),
+            ..self
+        }
+    }
+
+    /// Split the vertices across `shard_count` locks, selected by the first byte of the message ids, to reduce lock
+    /// contention. The shard count is rounded up to the next power of two. Insertions still lock all the shards they
+    /// touch at once, but reads spanning several vertices, like `edges`, may no longer observe them at the same point
+    /// in time once the tangle is sharded.
+    pub fn with_shards(self, shard_count: usize) -> Self {
+        let vertices = (0..shard_count.max(1).next_power_of_two())
+            .map(|_| {
+                #[cfg(feature = "metrics")]
+                if let Some(metrics) = self.metrics.as_ref() {
+                    return InstrumentedRwLock::with_metrics(HashMap::new(), metrics.clone());
+                }
+                InstrumentedRwLock::new(HashMap::new())
+            })
+            .collect();
+
+        Self { vertices, ..self }
+    }
+
+    /// Never let insertions grow the cache beyond `cap` vertices, even momentarily. Insertions that would exceed it
+    /// when eviction can't make room are refused with `InsertResult::CacheFull` instead. The cap should be set above
+    /// the capacity of the tangle, since eviction only kicks in beyond it. Messages only held by the hooks are not
+    /// pulled into a full cache either.
+    pub fn with_hard_cap(self, cap: usize) -> Self {
+        Self {
+            hard_cap: Some(cap),
+            ..self
+        }
+    }
+
+    /// Only accept the insertion of messages belonging to the network with the given id.
+    pub fn with_network_filter(self, network_id: u64) -> Self {
+        Self {
+            network_id: Some(network_id),
+            ..self
+        }
+    }
+
+    /// Only accept the insertion of messages whose serialized size is at most `bytes`.
+    pub fn with_max_message_size(self, bytes: usize) -> Self {
+        Self {
+            max_message_size: Some(bytes),
+            ..self
+        }
+    }
+
+    /// Make long traversals and scans yield to the scheduler every `interval` visited vertices, overriding
+    /// `DEFAULT_YIELD_INTERVAL`, so that they don't monopolize an executor thread. An interval of zero never yields.
+    pub fn with_yield_interval(self, interval: usize) -> Self {
+        Self {
+            yield_interval: interval,
+            ..self
+        }
+    }
+
+    /// Enable notifications of metadata changes, to be observed through `metadata_changed`. Observers lagging behind by
+    /// more than `capacity` changes miss the oldest ones.
+    pub fn with_metadata_notifications(self, capacity: usize) -> Self {
+        Self {
+            metadata_changed: Some(broadcast::channel(capacity).0),
+            ..self
+        }
+    }
+
+    /// Defer the persistence of metadata updates: instead of calling the hooks on every update, the updated vertices
+    /// are marked dirty and only written to the hooks by `flush_dirty`. Insertions are still written through. Dirty
+    /// vertices evicted before being flushed are pulled back from the dirty ones rather than from the hooks.
+    pub fn with_deferred_persistence(self) -> Self {
         Self {
-            cache_queue: Mutex::new(LruCache::with_hasher(cap + 1, DefaultHashBuilder::default())),
+            dirty: Some(Mutex::new(HashMap::new())),
             ..self
         }
     }
 
-    /// Change the maximum number of entries to store in the cache.
+    /// Register a callback invoked with every newly inserted vertex, e.g. to propagate solidity. The callback runs
+    /// while the write lock of the vertex is held: it must not call back into the tangle, or it will deadlock.
+    pub fn set_on_insert(&mut self, on_insert: impl Fn(&MessageId, &mut Vertex<T>) + Send + Sync + 'static) {
+        self.on_insert = Some(Box::new(on_insert));
+    }
+
+    /// Change the maximum number of entries to store in the cache. A length of zero is clamped to one, since an empty
+    /// cache would evict everything on every insertion.
     pub fn resize(&self, len: usize) {
-        self.max_len.store(len, Ordering::Relaxed);
+        self.max_len.store(len.max(1), Ordering::Relaxed);
+    }
+
+    /// Change the maximum number of entries to store in the cache like `resize`, then evict the vertices beyond it
+    /// right away. Returns the number of evicted vertices. Cancel safe: the new length applies right away, and the
+    /// eviction either fully happens or not at all.
+    pub async fn resize_and_evict(&self, new_len: usize) -> usize {
+        self.resize(new_len);
+        self.perform_eviction().await
+    }
+
+    /// Return the maximum number of entries to store in the cache.
+    pub fn capacity(&self) -> usize {
+        self.max_len.load(Ordering::Relaxed)
     }
 
     /// Return a reference to the storage hooks used by this tangle.
@@ -134,69 +417,355 @@ where
         &self.hooks
     }
 
-    async fn insert_inner(&self, message_id: MessageId, message: Message, metadata: T) -> Option<MessageRef> {
-        let mut vertices = self.vertices.write().await;
-        let vtx = vertices.entry(message_id).or_insert_with(Vertex::empty);
+    fn shard(&self, message_id: &MessageId) -> &InstrumentedRwLock<HashMap<MessageId, Vertex<T>>> {
+        &self.vertices[self.shard_index(message_id)]
+    }
 
-        let msg = if vtx.message().is_some() {
-            None
-        } else {
-            let parents = message.parents().clone();
+    fn shard_index(&self, message_id: &MessageId) -> usize {
+        // The shard count is a power of two
+        message_id.as_ref()[0] as usize & (self.vertices.len() - 1)
+    }
 
-            vtx.insert_message_and_metadata(message, metadata);
-            let msg = vtx.message().cloned();
+    // Returns the vertex of `message_id` from its locked shard, creating an empty one if it doesn't exist yet.
+    fn vertex_entry<'a>(
+        &self,
+        vertices: &'a mut HashMap<MessageId, Vertex<T>>,
+        message_id: MessageId,
+    ) -> &'a mut Vertex<T> {
+        let cache_len = &self.cache_len;
+        vertices.entry(message_id).or_insert_with(|| {
+            cache_len.fetch_add(1, Ordering::Relaxed);
+            Vertex::empty()
+        })
+    }
 
-            let mut cache_queue = self.cache_queue.lock().await;
+    // Reserves `slots` vertices in the cache, unless that would exceed `hard_cap`. The reservation is released on drop.
+    fn reserve(&self, slots: usize, hard_cap: usize) -> Option<Reservation<'_>> {
+        self.cache_len
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |len| {
+                Some(len + slots).filter(|len| *len <= hard_cap)
+            })
+            .ok()
+            .map(|_| Reservation {
+                cache_len: &self.cache_len,
+                slots,
+            })
+    }
 
-            // Insert children for parents
-            for &parent in parents.iter() {
-                let children = vertices.entry(parent).or_insert_with(Vertex::empty);
-                children.add_child(message_id);
+    // Counts one more step of a long traversal or scan in `steps`, and yields to the scheduler every time the yield
+    // interval is reached.
+    pub(crate) async fn yield_every(&self, steps: &mut usize) {
+        *steps += 1;
+        if self.yield_interval != 0 && *steps % self.yield_interval == 0 {
+            sync::yield_now().await;
+        }
+    }
 
-                // Insert cache queue entry to track eviction priority
-                cache_queue.put(parent, ());
+    async fn insert_inner(&self, message_id: MessageId, message: Message, metadata: T) -> InsertResult {
+        // All the shards involved are locked in ascending order, then the cache queue, before anything is changed: the
+        // insertion either fully happens or not at all, even if this future is dropped part-way through.
+        let mut shards = (0..self.vertices.len()).map(|_| None).collect::<Vec<_>>();
+        for (index, shard) in self.vertices.iter().enumerate() {
+            if std::iter::once(&message_id)
+                .chain(message.parents().iter())
+                .any(|message_id| self.shard_index(message_id) == index)
+            {
+                shards[index] = Some(shard.write().await);
             }
+        }
+        let mut cache_queue = self.cache_queue.lock().await;
+
+        // Unwraps are fine because the shards of the message and its parents were all locked.
+        let vtx = self.vertex_entry(shards[self.shard_index(&message_id)].as_mut().unwrap(), message_id);
+
+        if let Some(msg) = vtx.message() {
+            return InsertResult::AlreadyPresent(msg.clone());
+        }
+
+        let parents = message.parents().clone();
+
+        vtx.insert_message_and_metadata(message, metadata);
+        if let Some(on_insert) = self.on_insert.as_ref() {
+            on_insert(&message_id, vtx);
+        }
+        // Unwrap is fine because the message was just inserted.
+        let msg = vtx.message().cloned().unwrap();
+
+        // Insert children for parents
+        for &parent in parents.iter() {
+            let children = self.vertex_entry(shards[self.shard_index(&parent)].as_mut().unwrap(), parent);
+            children.add_child(message_id);
 
             // Insert cache queue entry to track eviction priority
-            cache_queue.put(message_id, ());
+            cache_queue.put(parent, ());
+        }
 
-            msg
-        };
+        // Insert cache queue entry to track eviction priority
+        cache_queue.put(message_id, ());
 
-        drop(vertices);
+        drop(cache_queue);
+        drop(shards);
 
         self.perform_eviction().await;
 
-        msg
+        InsertResult::Inserted(msg)
     }
 
-    /// Inserts a message, and returns a thread-safe reference to it in case it didn't already exist.
+    /// Inserts a message, and returns a thread-safe reference to it in case it didn't already exist. Not cancel safe:
+    /// if dropped once the message is in the cache, it may never be written to the hooks.
     pub async fn insert(&self, message_id: MessageId, message: Message, metadata: T) -> Option<MessageRef> {
-        self.pull_message(&message_id).await;
+        match self.insert_if_absent(message_id, message, metadata).await {
+            InsertResult::Inserted(msg) => Some(msg),
+            InsertResult::AlreadyPresent(_) | InsertResult::Rejected | InsertResult::CacheFull => None,
+        }
+    }
+
+    /// Inserts many messages concurrently, with at most one insertion in flight per CPU. Each insertion contends for
+    /// the locks independently, and the results are returned in the same order as the messages. Not cancel safe: if
+    /// dropped part-way, only some of the messages may have been inserted, and the last ones may not have reached the
+    /// hooks.
+    pub async fn parallel_insert(&self, messages: Vec<(MessageId, Message, T)>) -> Vec<Option<MessageRef>> {
+        stream::iter(messages)
+            .map(|(message_id, message, metadata)| self.insert(message_id, message, metadata))
+            .buffered(num_cpus::get())
+            .collect()
+            .await
+    }
+
+    /// Inserts a message using its cached id, and returns a thread-safe reference to it if it didn't already exist. Not
+    /// cancel safe, like `insert`.
+    pub async fn insert_with_hash(&self, message: Message, metadata: T) -> Option<MessageRef> {
+        self.insert(message.hash(), message, metadata).await
+    }
+
+    /// Inserts a message if it doesn't already exist, and returns a thread-safe reference to either the inserted or the
+    /// already present message. The hooks are only called if the message was inserted. Messages from another network
+    /// than the one set `with_network_filter`, or larger than the size set `with_max_message_size`, are rejected. Not
+    /// cancel safe: the message is inserted in the cache atomically, but if dropped afterwards it may never be written
+    /// to the hooks.
+    pub async fn insert_if_absent(&self, message_id: MessageId, message: Message, metadata: T) -> InsertResult {
+        self.insert_checked(message_id, message, metadata, true).await
+    }
+
+    /// Inserts a message if it isn't already in the cache, like `insert_if_absent` but without first looking it up in
+    /// the hooks. This is meant for messages known to be new, e.g. when duplicates are filtered upstream: the hooks are
+    /// still written to when the message is inserted. Not cancel safe, like `insert_if_absent`.
+    pub async fn insert_if_absent_in_cache(
+        &self,
+        message_id: MessageId,
+        message: Message,
+        metadata: T,
+    ) -> InsertResult {
+        self.insert_checked(message_id, message, metadata, false).await
+    }
+
+    // Inserts a message after applying the filters, only looking it up in the hooks first if `pull` is set.
+    async fn insert_checked(&self, message_id: MessageId, message: Message, metadata: T, pull: bool) -> InsertResult {
+        let _in_flight = self.begin_insertion().await;
+
+        #[cfg(feature = "metrics")]
+        let _timer = self.metrics.as_ref().map(|m| m.insert_latency.start_timer());
+
+        if let Some(network_id) = self.network_id {
+            if message.network_id() != network_id {
+                info!(
+                    "Rejected message {:?} from network {} instead of {}",
+                    message_id,
+                    message.network_id(),
+                    network_id
+                );
+                return InsertResult::Rejected;
+            }
+        }
+
+        if let Some(max_message_size) = self.max_message_size {
+            let size = message.packed_len();
+            if size > max_message_size {
+                info!(
+                    "Rejected message {:?} of {} bytes, exceeding the maximum of {} bytes",
+                    message_id, size, max_message_size
+                );
+                return InsertResult::Rejected;
+            }
+        }
+
+        // Duplicates only refresh their eviction priority, skipping the insertion and eviction work
+        if let Some(msg) = self.get_inner(&message_id).await.and_then(|v| v.message().cloned()) {
+            return InsertResult::AlreadyPresent(msg);
+        }
+
+        let mut reservation = None;
+
+        if let Some(hard_cap) = self.hard_cap {
+            // The message may bring its parents into the cache along with it
+            let required = 1 + message.parents().len();
+
+            // Only the cache is looked up here: a message pulled from the hooks takes up a slot, so it may only be
+            // pulled below, once the slots are reserved
+            if !self.contains_inner(&message_id).await {
+                // The slots are reserved before inserting so that concurrent insertions can't all pass the check
+                reservation = match self.reserve(required, hard_cap) {
+                    Some(reservation) => Some(reservation),
+                    None => {
+                        self.perform_eviction().await;
+                        self.reserve(required, hard_cap)
+                    }
+                };
+
+                if reservation.is_none() {
+                    info!("Refused message {:?} as the tangle cache is full", message_id);
+                    return InsertResult::CacheFull;
+                }
+            }
+        }
+
+        if pull {
+            self.pull_message(&message_id).await;
+        }
+
+        let res = self.insert_inner(message_id, message.clone(), metadata.clone()).await;
+
+        // The inserted vertices are counted by now
+        drop(reservation);
+
+        if let InsertResult::Inserted(_) = res {
+            #[cfg(feature = "metrics")]
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.inserts.inc();
+            }
 
-        let msg = self.insert_inner(message_id, message.clone(), metadata.clone()).await;
+            if !self.hooks.is_noop() {
+                // Write parents to DB
+                for &parent in message.parents().iter() {
+                    self.hooks
+                        .insert_approver(parent, message_id)
+                        .await
+                        .unwrap_or_else(|e| info!("Failed to update approvers for message {:?}", e));
+                }
 
-        if msg.is_some() {
-            // Write parents to DB
-            for &parent in message.parents().iter() {
+                // Insert into backend using hooks
                 self.hooks
-                    .insert_approver(parent, message_id)
+                    .insert(message_id, message, metadata.clone())
                     .await
-                    .unwrap_or_else(|e| info!("Failed to update approvers for message {:?}", e));
+                    .unwrap_or_else(|e| info!("Failed to insert message {:?}", e));
             }
+        }
 
-            // Insert into backend using hooks
-            self.hooks
-                .insert(message_id, message, metadata.clone())
-                .await
-                .unwrap_or_else(|e| info!("Failed to insert message {:?}", e));
+        res
+    }
+
+    /// Pauses all insertions until `unfreeze` is called, for example while exporting a snapshot, and waits for the
+    /// insertions already in flight to complete, including their writes to the hooks. Insertions attempted in the
+    /// meantime wait for the tangle to be unfrozen instead of failing. If dropped before completing, insertions are
+    /// paused but some may still be in flight.
+    pub async fn freeze(&self) {
+        // Sequentially consistent along with `begin_insertion`, so that either `freeze` sees an insertion in flight or
+        // the insertion sees the tangle frozen
+        self.frozen.store(true, Ordering::SeqCst);
+
+        loop {
+            // Registered before checking the count so that the last insertion completing in between can't be missed
+            let drained = self.drained.notified();
+
+            if self.in_flight.load(Ordering::SeqCst) == 0 {
+                break;
+            }
+
+            drained.await;
+        }
+    }
+
+    /// Resumes insertions paused by `freeze`, waking up all the pending ones. Cancel safe.
+    pub async fn unfreeze(&self) {
+        self.frozen.store(false, Ordering::SeqCst);
+        self.unfrozen.notify_waiters();
+    }
+
+    /// Returns whether insertions are currently paused by `freeze`.
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.load(Ordering::SeqCst)
+    }
+
+    // Waits until the tangle is not frozen, and counts an insertion in flight until the returned guard is dropped.
+    async fn begin_insertion(&self) -> InFlight<'_> {
+        loop {
+            while self.is_frozen() {
+                // Registered before checking the flag again so that an `unfreeze` in between can't be missed
+                let unfrozen = self.unfrozen.notified();
+
+                if !self.is_frozen() {
+                    break;
+                }
+
+                unfrozen.await;
+            }
+
+            self.in_flight.fetch_add(1, Ordering::SeqCst);
+            let in_flight = InFlight {
+                in_flight: &self.in_flight,
+                drained: &self.drained,
+            };
+
+            // The tangle may have been frozen in between, in which case the insertion backs off until it is unfrozen
+            if !self.is_frozen() {
+                return in_flight;
+            }
         }
+    }
+
+    /// Directly installs a vertex in the cache along with its exhaustive list of children, for example when importing a
+    /// snapshot. Unlike `insert`, this neither derives child edges from the parents of the message, nor calls the
+    /// hooks, nor validates anything: it must only be used with trusted input. Cancel safe: the vertex is either fully
+    /// installed or not at all.
+    pub async fn import_vertex(&self, message_id: MessageId, message: Message, metadata: T, children: Vec<MessageId>) {
+        let _in_flight = self.begin_insertion().await;
+
+        let mut vertices = self.shard(&message_id).write().await;
+        let mut cache_queue = self.cache_queue.lock().await;
+        let vtx = self.vertex_entry(&mut vertices, message_id);
+
+        vtx.insert_message_and_metadata(message, metadata);
+        for child in children {
+            vtx.add_child(child);
+        }
+        vtx.set_exhaustive();
+
+        // Insert cache queue entry to track eviction priority
+        cache_queue.put(message_id, ());
+
+        drop(cache_queue);
+        drop(vertices);
 
-        msg
+        self.perform_eviction().await;
+    }
+
+    /// Inserts a message, and returns a thread-safe reference to it in case it didn't already exist. If it did already
+    /// exist, the given metadata is combined with the existing metadata using `merge` instead of being discarded.
+    /// Messages rejected by the filters or refused by the hard cap are neither inserted nor merged. Not cancel safe: if
+    /// dropped after the insertion, the metadata may neither be merged nor written to the hooks.
+    pub async fn insert_or_merge<Merge>(
+        &self,
+        message_id: MessageId,
+        message: Message,
+        metadata: T,
+        merge: Merge,
+    ) -> Option<MessageRef>
+    where
+        T: PartialEq,
+        Merge: FnOnce(&mut T, T),
+    {
+        match self.insert_if_absent(message_id, message, metadata.clone()).await {
+            InsertResult::Inserted(msg) => Some(msg),
+            InsertResult::AlreadyPresent(_) => {
+                self.update_metadata(&message_id, |m| merge(m, metadata)).await;
+                None
+            }
+            InsertResult::Rejected | InsertResult::CacheFull => None,
+        }
     }
 
     async fn get_inner(&self, message_id: &MessageId) -> Option<impl Deref<Target = Vertex<T>> + '_> {
-        let res = TRwLockReadGuard::try_map(self.vertices.read().await, |m| m.get(message_id)).ok();
+        let res = InstrumentedReadGuard::try_map(self.shard(message_id).read().await, |m| m.get(message_id)).ok();
 
         if res.is_some() {
             // Update message_id priority
@@ -206,86 +775,537 @@ where
         res
     }
 
-    /// Get the data of a vertex associated with the given `message_id`.
+    /// Get the data of a vertex associated with the given `message_id`. Cancel safe: a message fetched from the hooks
+    /// is either fully inserted in the cache or not at all.
     pub async fn get(&self, message_id: &MessageId) -> Option<MessageRef> {
         self.pull_message(message_id).await;
 
         self.get_inner(message_id).await.and_then(|v| v.message().cloned())
     }
 
+    /// Get the messages with the given ids, in the same order, with `None` for the missing ones. The cache is read
+    /// taking the lock of each shard involved only once, and only the messages that are not in the cache are then
+    /// pulled from the hooks. Cancel safe, although if dropped part-way only some of the missing messages may have been
+    /// pulled from the hooks.
+    pub async fn get_in_order(&self, message_ids: &[MessageId]) -> Vec<Option<MessageRef>> {
+        let mut messages = vec![None; message_ids.len()];
+
+        for (index, shard) in self.vertices.iter().enumerate() {
+            if !message_ids
+                .iter()
+                .any(|message_id| self.shard_index(message_id) == index)
+            {
+                continue;
+            }
+
+            let vertices = shard.read().await;
+            for (message_id, message) in message_ids.iter().zip(messages.iter_mut()) {
+                if self.shard_index(message_id) == index {
+                    *message = vertices.get(message_id).and_then(|v| v.message().cloned());
+                }
+            }
+        }
+
+        {
+            let mut cache_queue = self.cache_queue.lock().await;
+            for (message_id, _) in message_ids.iter().zip(messages.iter()).filter(|(_, m)| m.is_some()) {
+                // Insert cache queue entry to track eviction priority
+                cache_queue.put(*message_id, ());
+            }
+        }
+
+        for (message_id, message) in message_ids.iter().zip(messages.iter_mut()) {
+            if message.is_none() {
+                *message = self.get(message_id).await;
+            }
+        }
+
+        messages
+    }
+
+    /// Get the data of a vertex associated with the given `message_id`, along with where it was found. Cancel safe,
+    /// like `get`.
+    pub async fn get_with_source(&self, message_id: &MessageId) -> (Option<MessageRef>, Source) {
+        if let Some(msg) = self.get_inner(message_id).await.and_then(|v| v.message().cloned()) {
+            return (Some(msg), Source::Cache);
+        }
+
+        if self.pull_message(message_id).await {
+            if let Some(msg) = self.get_inner(message_id).await.and_then(|v| v.message().cloned()) {
+                return (Some(msg), Source::Backend);
+            }
+        }
+
+        (None, Source::Missing)
+    }
+
+    /// Get the data of a vertex associated with the given `message_id`, giving up with `Elapsed` if fetching it from
+    /// the hooks takes longer than `timeout`. The fetch runs in a task of its own, so an abandoned fetch still
+    /// completes in the background and brings the message into the cache. Cancel safe for the same reason.
+    pub async fn get_with_timeout(
+        self: &Arc<Self>,
+        message_id: &MessageId,
+        timeout: Duration,
+    ) -> Result<Option<MessageRef>, Elapsed>
+    where
+        T: Send + Sync + 'static,
+        H: Send + Sync + 'static,
+    {
+        let fetch = sync::spawn({
+            let tangle = self.clone();
+            let message_id = *message_id;
+            async move { tangle.pull_message(&message_id).await }
+        });
+
+        // A fetch that panicked is reported like a missing message
+        let _ = sync::timeout(timeout, fetch).await?;
+
+        Ok(self.get_inner(message_id).await.and_then(|v| v.message().cloned()))
+    }
+
     async fn contains_inner(&self, message_id: &MessageId) -> bool {
-        self.vertices
+        self.shard(message_id)
             .read()
             .await
             .get(message_id)
             .map_or(false, |v| v.message().is_some())
     }
 
-    /// Returns whether the message is stored in the Tangle.
+    /// Returns whether the message is stored in the Tangle. Cancel safe, like `get`.
     pub async fn contains(&self, message_id: &MessageId) -> bool {
         self.contains_inner(message_id).await || self.pull_message(message_id).await
     }
 
-    /// Get the metadata of a vertex associated with the given `message_id`.
+    /// Returns whether the message is in the cache, without looking it up in the hooks nor refreshing its eviction
+    /// priority. Cancel safe, as it only reads the cache.
+    pub async fn contains_in_cache(&self, message_id: &MessageId) -> bool {
+        self.contains_inner(message_id).await
+    }
+
+    /// Returns whether the message is stored by the hooks, regardless of the cache. The message is not brought into the
+    /// cache, leaving it untouched. Cancel safe as long as the `get` hook is.
+    pub async fn exists_in_backend(&self, message_id: &MessageId) -> Result<bool, H::Error> {
+        Ok(self.hooks.get(message_id).await?.is_some())
+    }
+
+    // Returns whether each of the messages is in the cache, taking the lock of each shard involved only once.
+    async fn contains_all_inner(&self, message_ids: &[MessageId]) -> Vec<bool> {
+        let mut contained = vec![false; message_ids.len()];
+
+        for (index, shard) in self.vertices.iter().enumerate() {
+            if !message_ids
+                .iter()
+                .any(|message_id| self.shard_index(message_id) == index)
+            {
+                continue;
+            }
+
+            let vertices = shard.read().await;
+            for (message_id, contained) in message_ids.iter().zip(contained.iter_mut()) {
+                if self.shard_index(message_id) == index {
+                    *contained = vertices.get(message_id).map_or(false, |v| v.message().is_some());
+                }
+            }
+        }
+
+        contained
+    }
+
+    /// Returns whether all the messages are stored in the Tangle, only pulling from the hooks those that are not in the
+    /// cache until one is found to be missing. Cancel safe, like `get`.
+    pub async fn contains_all(&self, message_ids: &[MessageId]) -> bool {
+        for (message_id, contained) in message_ids.iter().zip(self.contains_all_inner(message_ids).await) {
+            if !contained && !self.pull_message(message_id).await {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Returns whether any of the messages is stored in the Tangle, only pulling from the hooks if none of them is in
+    /// the cache, until one is found. Cancel safe, like `get`.
+    pub async fn contains_any(&self, message_ids: &[MessageId]) -> bool {
+        if self
+            .contains_all_inner(message_ids)
+            .await
+            .into_iter()
+            .any(|contained| contained)
+        {
+            return true;
+        }
+
+        for message_id in message_ids {
+            if self.pull_message(message_id).await {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Get the metadata of a vertex associated with the given `message_id`. Cancel safe, like `get`.
     pub async fn get_metadata(&self, message_id: &MessageId) -> Option<T> {
         self.pull_message(message_id).await;
 
         self.get_metadata_maybe(message_id).await
     }
 
-    /// Get the metadata of a vertex associated with the given `message_id`, if it's in the cache.
+    /// Get the metadata of a vertex associated with the given `message_id`, falling back to `default` if the message is
+    /// known but its metadata isn't. Unlike `get_metadata`, this only returns `None` if the message is unknown. Cancel
+    /// safe, like `get`.
+    pub async fn get_metadata_or(&self, message_id: &MessageId, default: T) -> Option<T> {
+        self.pull_message(message_id).await;
+
+        self.get_inner(message_id)
+            .await
+            .and_then(|v| v.message().map(|_| v.metadata().cloned().unwrap_or(default)))
+    }
+
+    /// Get the data of a vertex associated with the given `message_id`, only if its metadata satisfies `predicate`.
+    /// Cancel safe, like `get`.
+    pub async fn get_if(&self, message_id: &MessageId, predicate: impl Fn(&T) -> bool) -> Option<MessageRef> {
+        self.pull_message(message_id).await;
+
+        self.get_inner(message_id).await.and_then(|v| {
+            v.message_and_metadata()
+                .filter(|(_, m)| predicate(m))
+                .map(|(m, _)| m.clone())
+        })
+    }
+
+    /// Get the data and metadata of a vertex associated with the given `message_id`. Cancel safe, like `get`.
+    pub async fn get_with_metadata(&self, message_id: &MessageId) -> Option<(MessageRef, T)> {
+        self.pull_message(message_id).await;
+
+        self.get_inner(message_id)
+            .await
+            .and_then(|v| v.message_and_metadata().cloned())
+    }
+
+    /// Get the data of a vertex associated with the given `message_id`, along with its metadata if it has any. Unlike
+    /// `get_with_metadata`, this returns the message even if its metadata is missing. Cancel safe, like `get`.
+    pub async fn get_with_metadata_or_default(&self, message_id: &MessageId) -> Option<(MessageRef, Option<T>)> {
+        self.pull_message(message_id).await;
+
+        self.get_inner(message_id)
+            .await
+            .and_then(|v| v.message().map(|message| (message.clone(), v.metadata().cloned())))
+    }
+
+    /// Get the metadata of a vertex associated with the given `message_id`, if it's in the cache. Cancel safe, as it
+    /// only reads the cache.
     pub async fn get_metadata_maybe(&self, message_id: &MessageId) -> Option<T> {
         self.get_inner(message_id).await.and_then(|v| v.metadata().cloned())
     }
 
-    /// Get the metadata of a vertex associated with the given `message_id`.
+    /// Get the metadata of a vertex associated with the given `message_id`. Cancel safe, like `get`.
     pub async fn get_vertex(&self, message_id: &MessageId) -> Option<impl Deref<Target = Vertex<T>> + '_> {
         self.pull_message(message_id).await;
 
         self.get_inner(message_id).await
     }
 
-    /// Updates the metadata of a particular vertex.
-    pub async fn set_metadata(&self, message_id: &MessageId, metadata: T) {
-        self.update_metadata(message_id, |m| *m = metadata).await;
+    /// Get an owned copy of a vertex associated with the given `message_id`. Unlike `get_vertex`, the lock is released
+    /// immediately so the copy can be held across await points. Cancel safe, like `get`.
+    pub async fn get_vertex_snapshot(&self, message_id: &MessageId) -> Option<VertexSnapshot<T>> {
+        self.get_vertex(message_id).await.map(|v| VertexSnapshot::from(&*v))
     }
 
-    /// Updates the metadata of a vertex.
-    pub async fn update_metadata<R, Update>(&self, message_id: &MessageId, update: Update) -> Option<R>
+    /// Updates the metadata of a particular vertex. Not cancel safe, like `update_metadata`.
+    pub async fn set_metadata(&self, message_id: &MessageId, metadata: T)
     where
-        Update: FnOnce(&mut T) -> R,
+        T: PartialEq,
     {
-        self.pull_message(message_id).await;
-        let mut vertices = self.vertices.write().await;
-        if let Some(vtx) = vertices.get_mut(message_id) {
-            let r = vtx.metadata_mut().map(|m| update(m));
-            if let Some((msg, meta)) = vtx.message_and_metadata() {
-                let (msg, meta) = ((&**msg).clone(), meta.clone());
+        self.update_metadata(message_id, |m| *m = metadata).await;
+    }
+
+    /// Replaces the metadata of a vertex, returning the previous metadata. Not cancel safe, like `update_metadata`.
+    pub async fn replace_metadata(&self, message_id: &MessageId, metadata: T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        self.update_metadata(message_id, |m| std::mem::replace(m, metadata))
+            .await
+    }
+
+    /// Updates the metadata of a vertex. Updates leaving the metadata unchanged are neither notified nor written to the
+    /// hooks. Not cancel safe: the cache is updated atomically, but if dropped afterwards the update may neither be
+    /// written to the hooks nor marked dirty.
+    pub async fn update_metadata<R, Update>(&self, message_id: &MessageId, update: Update) -> Option<R>
+    where
+        T: PartialEq,
+        Update: FnOnce(&mut T) -> R,
+    {
+        self.pull_message(message_id).await;
+        let mut vertices = self.shard(message_id).write().await;
+        let vtx = vertices.get_mut(message_id)?;
+        let (r, changed) = vtx.update_metadata(update)?;
+        let updated = if changed {
+            vtx.message_and_metadata()
+                .map(|(msg, meta)| ((&**msg).clone(), meta.clone()))
+        } else {
+            None
+        };
+
+        // Insert cache queue entry to track eviction priority
+        self.cache_queue.lock().await.put(*message_id, ());
+
+        drop(vertices);
+
+        if let Some((msg, meta)) = updated {
+            self.notify_metadata_changed(message_id, &meta);
+
+            self.persist_metadata(*message_id, msg, meta).await;
+        }
+
+        Some(r)
+    }
+
+    /// Updates the metadata of many vertices at once, taking the write lock of each shard involved only once. The
+    /// results are returned in the same order as the updates, and the hooks are then called concurrently for the
+    /// metadata that changed. Not cancel safe: if dropped part-way, only the updates of some shards may have been
+    /// applied, and none may have reached the hooks.
+    pub async fn update_metadata_batch<R, Update, I>(&self, updates: I) -> Vec<Option<R>>
+    where
+        T: PartialEq,
+        I: IntoIterator<Item = (MessageId, Update)>,
+        Update: FnOnce(&mut T) -> R,
+    {
+        // Updates are taken out as their shard is processed
+        let mut updates = updates.into_iter().map(Some).collect::<Vec<_>>();
+
+        for (message_id, _) in updates.iter().flatten() {
+            self.pull_message(message_id).await;
+        }
+
+        let mut results = updates.iter().map(|_| None).collect::<Vec<_>>();
+        let mut updated = Vec::new();
+
+        for (index, shard) in self.vertices.iter().enumerate() {
+            let in_shard = |update: &Option<(MessageId, Update)>| {
+                update
+                    .as_ref()
+                    .map_or(false, |(message_id, _)| self.shard_index(message_id) == index)
+            };
+
+            if !updates.iter().any(in_shard) {
+                continue;
+            }
+
+            let mut vertices = shard.write().await;
+            let mut cache_queue = self.cache_queue.lock().await;
+
+            for (update, result) in updates.iter_mut().zip(results.iter_mut()) {
+                if !in_shard(&*update) {
+                    continue;
+                }
+                // Unwrap is fine because the update was just checked to be there.
+                let (message_id, update) = update.take().unwrap();
+
+                if let Some(vtx) = vertices.get_mut(&message_id) {
+                    if let Some((r, changed)) = vtx.update_metadata(update) {
+                        *result = Some(r);
+
+                        if changed {
+                            if let Some((msg, meta)) = vtx.message_and_metadata() {
+                                updated.push((message_id, (&**msg).clone(), meta.clone()));
+                            }
+                        }
+
+                        // Insert cache queue entry to track eviction priority
+                        cache_queue.put(message_id, ());
+                    }
+                }
+            }
+        }
+
+        for (message_id, _, meta) in updated.iter() {
+            self.notify_metadata_changed(message_id, meta);
+        }
+
+        join_all(
+            updated
+                .into_iter()
+                .map(|(message_id, msg, meta)| self.persist_metadata(message_id, msg, meta)),
+        )
+        .await;
+
+        results
+    }
+
+    /// Get the metadata of a vertex associated with the given `message_id`, along with its current version. Cancel
+    /// safe, like `get`.
+    pub async fn get_metadata_versioned(&self, message_id: &MessageId) -> Option<(T, u64)> {
+        self.pull_message(message_id).await;
+
+        self.get_inner(message_id)
+            .await
+            .and_then(|v| v.metadata().cloned().map(|m| (m, v.metadata_version())))
+    }
+
+    /// Replaces the metadata of a vertex, only if its version is still `expected_version`. Returns whether the metadata
+    /// was replaced. Not cancel safe, like `update_metadata`.
+    pub async fn compare_and_set_metadata(&self, message_id: &MessageId, expected_version: u64, metadata: T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.pull_message(message_id).await;
+        let mut vertices = self.shard(message_id).write().await;
+        match vertices.get_mut(message_id) {
+            Some(vtx) if vtx.metadata_version() == expected_version => {
+                let changed = match vtx.update_metadata(|m| *m = metadata) {
+                    Some(((), changed)) => changed,
+                    None => return false,
+                };
+                let updated = if changed {
+                    vtx.message_and_metadata()
+                        .map(|(msg, meta)| ((&**msg).clone(), meta.clone()))
+                } else {
+                    None
+                };
 
                 // Insert cache queue entry to track eviction priority
                 self.cache_queue.lock().await.put(*message_id, ());
 
                 drop(vertices);
 
-                self.hooks
-                    .insert(*message_id, msg, meta)
-                    .await
-                    .unwrap_or_else(|e| info!("Failed to update metadata for message {:?}", e));
+                if let Some((msg, meta)) = updated {
+                    self.notify_metadata_changed(message_id, &meta);
+
+                    self.persist_metadata(*message_id, msg, meta).await;
+                }
+
+                true
             }
+            _ => false,
+        }
+    }
 
-            r
-        } else {
-            None
+    /// Writes updated metadata to the hooks, or marks the vertex dirty if persistence is deferred.
+    async fn persist_metadata(&self, message_id: MessageId, message: Message, metadata: T) {
+        if let Some(dirty) = self.dirty.as_ref() {
+            dirty.lock().await.insert(message_id, (message, metadata));
+        } else if !self.hooks.is_noop() {
+            self.hooks
+                .insert(message_id, message, metadata)
+                .await
+                .unwrap_or_else(|e| info!("Failed to update metadata for message {:?}", e));
         }
     }
 
-    /// Returns the number of messages in the Tangle.
+    /// Writes the metadata of all the dirty vertices to the hooks, and returns how many were written. Vertices that
+    /// failed to be written, or were not reached because of a failure, stay dirty. Does nothing unless the tangle was
+    /// created `with_deferred_persistence`. Not cancel safe: the vertices that are not written yet when it is dropped
+    /// are no longer dirty, so their metadata only reaches the hooks on their next update.
+    pub async fn flush_dirty(&self) -> Result<usize, H::Error> {
+        let dirty = match self.dirty.as_ref() {
+            Some(dirty) => dirty,
+            None => return Ok(0),
+        };
+
+        let mut pending = std::mem::take(&mut *dirty.lock().await).into_iter();
+        let mut flushed = 0;
+
+        while let Some((message_id, (message, metadata))) = pending.next() {
+            if let Err(e) = self.hooks.insert(message_id, message.clone(), metadata.clone()).await {
+                // Newer updates made during the flush take precedence over the entries put back.
+                let mut dirty = dirty.lock().await;
+                for (message_id, entry) in std::iter::once((message_id, (message, metadata))).chain(pending) {
+                    dirty.entry(message_id).or_insert(entry);
+                }
+                return Err(e);
+            }
+            flushed += 1;
+        }
+
+        Ok(flushed)
+    }
+
+    async fn dirty_entry(&self, message_id: &MessageId) -> Option<(Message, T)> {
+        match self.dirty.as_ref() {
+            Some(dirty) => dirty.lock().await.get(message_id).cloned(),
+            None => None,
+        }
+    }
+
+    /// Returns a stream of metadata changes, carrying the new metadata. Updates leaving the metadata unchanged are not
+    /// notified. The stream is empty unless the tangle was created `with_metadata_notifications`.
+    pub fn metadata_changed(&self) -> impl Stream<Item = StreamEvent<(MessageId, T)>> {
+        event::broadcast_stream(self.metadata_changed.as_ref().map(|tx| tx.subscribe()))
+    }
+
+    fn notify_metadata_changed(&self, message_id: &MessageId, metadata: &T) {
+        event::broadcast(self.metadata_changed.as_ref(), || (*message_id, metadata.clone()));
+    }
+
+    /// Returns whether the whole past cone of a vertex is known to be available, if it's in the cache. The flag is lost
+    /// when the vertex is evicted, so `false` only means that the past cone has to be checked again, e.g. against the
+    /// metadata. Cancel safe, as it only reads the cache.
+    pub async fn has_complete_past_cone(&self, message_id: &MessageId) -> bool {
+        self.shard(message_id)
+            .read()
+            .await
+            .get(message_id)
+            .map_or(false, |v| v.has_complete_past_cone())
+    }
+
+    /// Flags that the whole past cone of a vertex is available, once it is solid, so that its past cone need not be
+    /// traversed again while the vertex is in the cache. Does nothing if the vertex is not in the cache. Cancel safe.
+    pub async fn set_past_cone_complete(&self, message_id: &MessageId) {
+        if let Some(vtx) = self.shard(message_id).write().await.get_mut(message_id) {
+            vtx.set_past_cone_complete();
+        }
+    }
+
+    /// Returns the number of messages in the Tangle. Cancel safe, as it only reads the cache.
     pub async fn len(&self) -> usize {
-        // Does not take GTL because this is effectively atomic
-        self.vertices.read().await.len()
+        let mut len = 0;
+        for shard in self.vertices.iter() {
+            len += shard.read().await.len();
+        }
+        len
+    }
+
+    /// Returns a snapshot of the state of the tangle, taking the lock of each shard only once. Cancel safe, as it only
+    /// reads the cache.
+    pub async fn stats(&self) -> TangleStats {
+        let (mut vertices, mut messages) = (0, 0);
+        for shard in self.vertices.iter() {
+            let shard = shard.read().await;
+            vertices += shard.len();
+            messages += shard.values().filter(|v| v.message().is_some()).count();
+        }
+
+        let capacity = self.capacity();
+
+        #[cfg(feature = "metrics")]
+        let counters = self.metrics.as_ref().map(|m| {
+            [
+                m.inserts.get(),
+                m.evictions.get(),
+                m.hook_hits.get(),
+                m.hook_misses.get(),
+            ]
+        });
+        #[cfg(not(feature = "metrics"))]
+        let counters: Option<[f64; 4]> = None;
+
+        let counter = |index: usize| counters.map(|counters| counters[index] as u64);
+
+        TangleStats {
+            vertices,
+            messages,
+            capacity,
+            eviction_threshold: ((1.0 - CACHE_THRESHOLD_FACTOR) * capacity as f64) as usize,
+            inserts: counter(0),
+            evictions: counter(1),
+            hook_hits: counter(2),
+            hook_misses: counter(3),
+        }
     }
 
-    /// Checks if the tangle is empty.
+    /// Checks if the tangle is empty. Cancel safe, as it only reads the cache.
     pub async fn is_empty(&self) -> bool {
         self.len().await == 0
     }
@@ -304,7 +1324,7 @@ where
             }
         }
 
-        let vertices = self.vertices.read().await;
+        let vertices = self.shard(message_id).read().await;
         let v = vertices
             .get(message_id)
             // Skip approver lists that are not exhaustive
@@ -322,17 +1342,23 @@ where
                 // Insert cache queue entry to track eviction priority
                 self.cache_queue.lock().await.put(*message_id, ());
                 drop(vertices);
-                let to_insert = match self.hooks.fetch_approvers(message_id).await {
-                    Err(e) => {
-                        info!("Failed to update approvers for message message {:?}", e);
-                        Vec::new()
+                let to_insert = if self.hooks.is_noop() {
+                    Vec::new()
+                } else {
+                    match self.hooks.fetch_approvers(message_id).await {
+                        Err(e) => {
+                            info!("Failed to update approvers for message message {:?}", e);
+                            Vec::new()
+                        }
+                        Ok(None) => Vec::new(),
+                        Ok(Some(approvers)) => approvers,
                     }
-                    Ok(None) => Vec::new(),
-                    Ok(Some(approvers)) => approvers,
                 };
 
-                let mut vertices = self.vertices.write().await;
-                let v = vertices.entry(*message_id).or_insert_with(Vertex::empty);
+                let mut vertices = self.shard(message_id).write().await;
+                // The entry put before fetching may have been evicted in the meantime
+                self.cache_queue.lock().await.put(*message_id, ());
+                let v = self.vertex_entry(&mut vertices, *message_id);
 
                 // We've just fetched approvers from the database, so we have all the information available to us now.
                 // Therefore, the approvers list is exhaustive (i.e: it contains all knowledge we have).
@@ -352,13 +1378,561 @@ where
         })
     }
 
-    /// Returns the children of a vertex, if we know about them.
+    /// Returns the children of a vertex, if we know about them. Cancel safe: children fetched from the hooks are either
+    /// all recorded in the cache or not at all.
     pub async fn get_children(&self, message_id: &MessageId) -> Option<Vec<MessageId>> {
         // Effectively atomic
         self.children_inner(message_id).await.map(|approvers| approvers.clone())
     }
 
-    /// Returns the number of children of a vertex.
+    /// Fetches from the hooks the exhaustive children of the given vertices, with at most `concurrency` fetches in
+    /// flight, so that later lookups of their children don't stall on the hooks. Vertices whose children are already
+    /// exhaustively known are skipped. A `concurrency` of zero means no limit. Cancel safe, although if dropped
+    /// part-way only the children of some of the vertices may have been fetched.
+    pub async fn warm_children(&self, message_ids: impl Stream<Item = MessageId>, concurrency: usize) {
+        message_ids
+            .for_each_concurrent(concurrency, |message_id| async move {
+                let exhaustive = self
+                    .shard(&message_id)
+                    .read()
+                    .await
+                    .get(&message_id)
+                    .map_or(false, |v| v.children_exhaustive());
+
+                if !exhaustive {
+                    self.children_inner(&message_id).await;
+                }
+            })
+            .await
+    }
+
+    /// Returns the children of a vertex, if we know about them, without copying them. The returned guard keeps the
+    /// vertex read-locked, so it should be dropped before calling back into the tangle. Cancel safe, like
+    /// `get_children`.
+    pub async fn children_guard(&self, message_id: &MessageId) -> Option<impl Deref<Target = [MessageId]> + '_> {
+        InstrumentedReadGuard::try_map(self.exhaustive_vertex(message_id).await?, |v| Some(v.children())).ok()
+    }
+
+    // Returns the read-locked vertex, after making its children exhaustive.
+    async fn exhaustive_vertex(&self, message_id: &MessageId) -> Option<InstrumentedReadGuard<'_, Vertex<T>>> {
+        let mut vertices = self.shard(message_id).read().await;
+
+        if !vertices.get(message_id).map_or(false, |v| v.children_exhaustive()) {
+            drop(vertices);
+            // Fetch the approvers from the hooks and make the children exhaustive
+            self.children_inner(message_id).await?;
+            vertices = self.shard(message_id).read().await;
+        }
+
+        // Insert cache queue entry to track eviction priority
+        self.cache_queue.lock().await.put(*message_id, ());
+
+        InstrumentedReadGuard::try_map(vertices, |m| m.get(message_id)).ok()
+    }
+
+    /// Returns the children of a vertex added after the given revision, along with the revision to pass to get the
+    /// next ones, if we know about them. Start from revision zero to get all the children. Revisions only ever
+    /// increase, but a vertex that was evicted gets new revisions for its children when fetched again: all of them are
+    /// then returned again, and callers should resynchronise with them rather than expect only new children. Cancel
+    /// safe, like `get_children`.
+    pub async fn children_since(&self, message_id: &MessageId, revision: u64) -> Option<(Vec<MessageId>, u64)> {
+        Some(self.exhaustive_vertex(message_id).await?.children_since(revision))
+    }
+
+    /// Returns the children of a vertex in ascending order, if we know about them. Unlike `get_children`, the order
+    /// doesn't depend on the order in which the children were inserted. Cancel safe, like `get_children`.
+    pub async fn get_children_sorted(&self, message_id: &MessageId) -> Option<Vec<MessageId>> {
+        self.get_children(message_id).await.map(|mut children| {
+            children.sort_unstable();
+            children
+        })
+    }
+
+    /// Returns the children of a vertex, only if they are exhaustively known by the cache. Unlike `get_children`, this
+    /// never calls out to the hooks. Cancel safe, as it only reads the cache.
+    pub async fn get_children_cached(&self, message_id: &MessageId) -> Option<Vec<MessageId>> {
+        self.shard(message_id)
+            .read()
+            .await
+            .get(message_id)
+            // Skip approver lists that are not exhaustive
+            .filter(|v| v.children_exhaustive())
+            .map(|v| v.children().to_vec())
+    }
+
+    /// Returns every `(parent, child)` edge currently held in the cache. This only reflects what is in memory: approver
+    /// lists that have not been fetched from the hooks may not be exhaustive. Cancel safe, as it only reads the cache.
+    pub async fn edges(&self) -> Vec<(MessageId, MessageId)> {
+        let mut edges = Vec::new();
+        for shard in self.vertices.iter() {
+            edges.extend(
+                shard
+                    .read()
+                    .await
+                    .iter()
+                    .flat_map(|(parent, v)| v.children().iter().map(move |child| (*parent, *child))),
+            );
+        }
+        edges
+    }
+
+    /// Writes a Graphviz `digraph` of the vertices currently held in the cache, with edges going from parents to
+    /// children. Vertices whose metadata satisfies `is_solid` are filled in green, and vertices satisfying `is_tip` are
+    /// outlined in bold. If dropped part-way, only part of the graph may have been written.
+    pub async fn write_graphviz<W, F, G>(&self, mut writer: W, is_solid: F, is_tip: G) -> io::Result<()>
+    where
+        W: Write,
+        F: Fn(&T) -> bool,
+        G: Fn(&MessageId) -> bool,
+    {
+        writeln!(writer, "digraph {{")?;
+
+        for shard in self.vertices.iter() {
+            // Rendered before writing so that the lock is not held across potentially blocking writes
+            let mut buf = Vec::new();
+
+            for (message_id, v) in shard.read().await.iter() {
+                let id = message_id.to_string();
+                let mut style = Vec::new();
+
+                if v.metadata().map_or(false, &is_solid) {
+                    style.push("filled");
+                }
+                if is_tip(message_id) {
+                    style.push("bold");
+                }
+
+                writeln!(
+                    buf,
+                    "    \"{}\" [label=\"{}\", style=\"{}\", fillcolor=green];",
+                    id,
+                    &id[..8],
+                    style.join(",")
+                )?;
+                for child in v.children() {
+                    writeln!(buf, "    \"{}\" -> \"{}\";", id, child)?;
+                }
+            }
+
+            writer.write_all(&buf)?;
+        }
+
+        writeln!(writer, "}}")
+    }
+
+    /// Visits every message in the cache, `chunk` messages at a time. The lock is only held while collecting each chunk
+    /// so that writers are never blocked for the whole scan, at the expense of consistency: messages inserted during
+    /// the scan may or may not be visited, and messages evicted during it are skipped. Visiting messages does not
+    /// affect their eviction priority. Cancel safe: dropping it simply stops the visit.
+    pub async fn visit_all<F>(&self, chunk: usize, mut f: F)
+    where
+        F: FnMut(&[(MessageId, MessageRef)]),
+    {
+        let mut visited = 0;
+
+        for shard in self.vertices.iter() {
+            let message_ids = shard.read().await.keys().copied().collect::<Vec<_>>();
+
+            for message_ids in message_ids.chunks(chunk.max(1)) {
+                let messages = {
+                    let vertices = shard.read().await;
+                    message_ids
+                        .iter()
+                        .filter_map(|message_id| {
+                            vertices
+                                .get(message_id)
+                                .and_then(|v| v.message())
+                                .map(|message| (*message_id, message.clone()))
+                        })
+                        .collect::<Vec<_>>()
+                };
+
+                if !messages.is_empty() {
+                    f(&messages);
+                }
+
+                // Chunks may step over multiples of the interval, so yield whenever one is crossed
+                let before = visited;
+                visited += message_ids.len();
+                if self.yield_interval != 0 && before / self.yield_interval != visited / self.yield_interval {
+                    sync::yield_now().await;
+                }
+            }
+        }
+    }
+
+    /// Returns the number of children of a vertex. Unlike `num_children`, this does not copy the children when they are
+    /// exhaustively known by the cache. Cancel safe, like `get_children`.
+    pub async fn get_children_count(&self, message_id: &MessageId) -> usize {
+        let count = self
+            .shard(message_id)
+            .read()
+            .await
+            .get(message_id)
+            // Skip approver lists that are not exhaustive
+            .filter(|v| v.children_exhaustive())
+            .map(|v| v.children().len());
+
+        match count {
+            Some(count) => {
+                // Insert cache queue entry to track eviction priority
+                self.cache_queue.lock().await.put(*message_id, ());
+                count
+            }
+            None => self.num_children(message_id).await,
+        }
+    }
+
+    /// Returns all the descendants of a vertex up to `max_depth` levels away from it. Since the children come from the
+    /// hooks, they can't be trusted to be acyclic: a `CycleError` is returned if a cycle is found. Cancel safe, as it
+    /// only fetches children like `get_children` does.
+    pub async fn get_all_children_recursive(
+        &self,
+        root: &MessageId,
+        max_depth: usize,
+    ) -> Result<HashSet<MessageId>, CycleError> {
+        // Shallowest depth at which each descendant was found, so that descendants first found deeper than the depth
+        // bound allows for their own descendants get explored again when found at a shallower depth.
+        let mut depths = HashMap::new();
+        let mut path = HashSet::new();
+        let mut stack = vec![(*root, self.get_children(root).await.unwrap_or_default(), 0)];
+        let mut steps = 0;
+
+        path.insert(*root);
+
+        loop {
+            self.yield_every(&mut steps).await;
+
+            let depth = stack.len();
+            let next = match stack.last_mut() {
+                Some((_, children, next)) if depth <= max_depth && *next < children.len() => {
+                    *next += 1;
+                    Some(children[*next - 1])
+                }
+                Some(_) => None,
+                None => break,
+            };
+
+            match next {
+                Some(child) => {
+                    if path.contains(&child) {
+                        return Err(CycleError(child));
+                    }
+
+                    if depths.get(&child).map_or(true, |d| depth < *d) {
+                        depths.insert(child, depth);
+                        path.insert(child);
+                        let children = self.get_children(&child).await.unwrap_or_default();
+                        stack.push((child, children, 0));
+                    }
+                }
+                None => {
+                    if let Some((message_id, _, _)) = stack.pop() {
+                        path.remove(&message_id);
+                    }
+                }
+            }
+        }
+
+        Ok(depths.keys().copied().collect())
+    }
+
+    /// Returns the descendants of a message that have no children themselves, i.e. the tips referencing it directly or
+    /// indirectly. Cancel safe, as it only fetches children like `get_children` does.
+    pub async fn reachable_tips_from(&self, root: &MessageId) -> Vec<MessageId> {
+        let mut tips = Vec::new();
+        let mut visited = HashSet::new();
+        let mut queue = self
+            .get_children(root)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<VecDeque<_>>();
+        let mut steps = 0;
+
+        while let Some(message_id) = queue.pop_front() {
+            self.yield_every(&mut steps).await;
+
+            if !visited.insert(message_id) {
+                continue;
+            }
+
+            let children = self.get_children(&message_id).await.unwrap_or_default();
+
+            if children.is_empty() {
+                tips.push(message_id);
+            } else {
+                queue.extend(children);
+            }
+        }
+
+        tips
+    }
+
+    /// Returns the unique approvers reachable from `root` within `max_depth` hops, excluding `root` itself, in
+    /// breadth-first order. Children that aren't exhaustively known by the cache are fetched from the hooks. Cancel
+    /// safe, as it only fetches children like `get_children` does.
+    pub async fn get_descendants(&self, root: &MessageId, max_depth: usize) -> Vec<MessageId> {
+        let mut descendants = Vec::new();
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+        let mut steps = 0;
+
+        visited.insert(*root);
+        queue.push_back((*root, 0));
+
+        while let Some((message_id, depth)) = queue.pop_front() {
+            self.yield_every(&mut steps).await;
+
+            if depth == max_depth {
+                continue;
+            }
+
+            for child in self.get_children(&message_id).await.unwrap_or_default() {
+                if visited.insert(child) {
+                    descendants.push(child);
+                    queue.push_back((child, depth + 1));
+                }
+            }
+        }
+
+        descendants
+    }
+
+    /// Returns the number of children of each of the given vertices, in the same order. Children exhaustively known by
+    /// the cache are counted first, and the remaining ones are fetched from the hooks in a single batch. Either way,
+    /// the lock of each shard involved is only taken once. Cancel safe, although if dropped part-way only some of the
+    /// fetched children may have been recorded in the cache.
+    pub async fn num_children_batch(&self, message_ids: &[MessageId]) -> Vec<usize> {
+        let mut counts = vec![None; message_ids.len()];
+
+        for (index, shard) in self.vertices.iter().enumerate() {
+            if !message_ids
+                .iter()
+                .any(|message_id| self.shard_index(message_id) == index)
+            {
+                continue;
+            }
+
+            let vertices = shard.read().await;
+            for (message_id, count) in message_ids.iter().zip(counts.iter_mut()) {
+                if self.shard_index(message_id) == index {
+                    *count = vertices
+                        .get(message_id)
+                        // Skip approver lists that are not exhaustive
+                        .filter(|v| v.children_exhaustive())
+                        .map(|v| v.children().len());
+                }
+            }
+        }
+
+        {
+            let mut cache_queue = self.cache_queue.lock().await;
+            for (message_id, _) in message_ids.iter().zip(counts.iter()).filter(|(_, c)| c.is_some()) {
+                // Insert cache queue entry to track eviction priority
+                cache_queue.put(*message_id, ());
+            }
+        }
+
+        let missing = message_ids
+            .iter()
+            .zip(counts.iter())
+            .filter(|(_, c)| c.is_none())
+            .map(|(message_id, _)| *message_id)
+            .collect::<Vec<_>>();
+
+        if missing.is_empty() {
+            return counts.into_iter().map(Option::unwrap_or_default).collect();
+        }
+
+        let mut fetched = self.hooks.fetch_approvers_batch(&missing).await.unwrap_or_else(|e| {
+            info!("Failed to update approvers for messages {:?}", e);
+            vec![None; missing.len()]
+        });
+
+        let mut fetched_counts = vec![0; missing.len()];
+
+        for (index, shard) in self.vertices.iter().enumerate() {
+            if !missing.iter().any(|message_id| self.shard_index(message_id) == index) {
+                continue;
+            }
+
+            let mut vertices = shard.write().await;
+            let mut cache_queue = self.cache_queue.lock().await;
+
+            let entries = missing.iter().zip(fetched.iter_mut()).zip(fetched_counts.iter_mut());
+
+            for ((message_id, approvers), count) in entries {
+                if self.shard_index(message_id) != index {
+                    continue;
+                }
+
+                // Insert cache queue entry to track eviction priority
+                cache_queue.put(*message_id, ());
+                let v = self.vertex_entry(&mut vertices, *message_id);
+
+                // We've just fetched approvers from the database, so we have all the information available to us now.
+                // Therefore, the approvers list is exhaustive (i.e: it contains all knowledge we have).
+                v.set_exhaustive();
+
+                for child in approvers.take().unwrap_or_default() {
+                    v.add_child(child);
+                }
+
+                *count = v.children().len();
+            }
+        }
+
+        let mut fetched_counts = fetched_counts.into_iter();
+        counts
+            .into_iter()
+            .map(|count| count.unwrap_or_else(|| fetched_counts.next().unwrap_or_default()))
+            .collect()
+    }
+
+    /// Returns the past cone reachable from `roots` in topological order, such that parents always appear before their
+    /// children, pulling missing messages from the hooks. If the cone is not solid, the order only covers the known
+    /// messages and the parents that could not be found are returned alongside it. Cancel safe, as it only pulls
+    /// messages like `get` does.
+    pub async fn topological_order(&self, roots: &[MessageId]) -> (Vec<MessageId>, HashSet<MessageId>) {
+        let mut parents = HashMap::new();
+        let mut missing = HashSet::new();
+        let mut stack = roots.to_vec();
+        let mut steps = 0;
+
+        while let Some(message_id) = stack.pop() {
+            self.yield_every(&mut steps).await;
+
+            if parents.contains_key(&message_id) || missing.contains(&message_id) {
+                continue;
+            }
+
+            match self.get(&message_id).await {
+                Some(message) => {
+                    let message_parents = message.parents().iter().copied().collect::<Vec<_>>();
+                    stack.extend(message_parents.iter().copied());
+                    parents.insert(message_id, message_parents);
+                }
+                None => {
+                    missing.insert(message_id);
+                }
+            }
+        }
+
+        // Kahn's algorithm: a message is ready once all of its parents within the cone have been ordered.
+        let mut pending = HashMap::new();
+        let mut children = HashMap::<_, Vec<_>>::new();
+        let mut ready = VecDeque::new();
+
+        for (message_id, message_parents) in parents.iter() {
+            let known = message_parents.iter().filter(|p| parents.contains_key(p)).count();
+
+            for parent in message_parents.iter().filter(|p| parents.contains_key(p)) {
+                children.entry(*parent).or_default().push(*message_id);
+            }
+
+            if known == 0 {
+                ready.push_back(*message_id);
+            } else {
+                pending.insert(*message_id, known);
+            }
+        }
+
+        let mut order = Vec::with_capacity(parents.len());
+
+        while let Some(message_id) = ready.pop_front() {
+            for child in children.remove(&message_id).unwrap_or_default() {
+                if let Some(known) = pending.get_mut(&child) {
+                    *known -= 1;
+                    if *known == 0 {
+                        pending.remove(&child);
+                        ready.push_back(child);
+                    }
+                }
+            }
+
+            order.push(message_id);
+            self.yield_every(&mut steps).await;
+        }
+
+        (order, missing)
+    }
+
+    /// Walks the past cone of `root`, without descending past the vertices whose metadata satisfies `is_boundary`, and
+    /// returns these boundary vertices. Messages that can't be found are neither walked nor returned. Cancel safe, as
+    /// it only pulls messages like `get` does.
+    pub async fn past_cone_boundary(&self, root: &MessageId, is_boundary: impl Fn(&T) -> bool) -> Vec<MessageId> {
+        let mut boundary = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![*root];
+        let mut steps = 0;
+
+        while let Some(message_id) = stack.pop() {
+            self.yield_every(&mut steps).await;
+
+            if !visited.insert(message_id) {
+                continue;
+            }
+
+            if let Some((message, metadata)) = self.get_with_metadata(&message_id).await {
+                if is_boundary(&metadata) {
+                    boundary.push(message_id);
+                } else {
+                    stack.extend(message.parents().iter().copied());
+                }
+            }
+        }
+
+        boundary
+    }
+
+    /// Returns the closest common ancestor of two messages, or `None` if there is none within `max_depth` hops of
+    /// either of them. A message counts as its own ancestor, so if `b` is an ancestor of `a`, `b` is returned. Cancel
+    /// safe, as it only pulls messages like `get` does.
+    pub async fn find_common_ancestor(&self, a: &MessageId, b: &MessageId, max_depth: usize) -> Option<MessageId> {
+        if a == b {
+            return Some(*a);
+        }
+
+        let mut seen = [HashSet::new(), HashSet::new()];
+        let mut frontiers = [vec![*a], vec![*b]];
+        let mut steps = 0;
+
+        seen[0].insert(*a);
+        seen[1].insert(*b);
+
+        for _ in 0..max_depth {
+            // Alternately expand each frontier by one level, stopping as soon as it reaches a message seen by the other
+            for side in 0..2 {
+                let mut next = Vec::new();
+
+                for message_id in frontiers[side].iter() {
+                    self.yield_every(&mut steps).await;
+
+                    if let Some(message) = self.get(message_id).await {
+                        for parent in message.parents().iter() {
+                            if seen[1 - side].contains(parent) {
+                                return Some(*parent);
+                            }
+                            if seen[side].insert(*parent) {
+                                next.push(*parent);
+                            }
+                        }
+                    }
+                }
+
+                frontiers[side] = next;
+            }
+
+            if frontiers.iter().all(Vec::is_empty) {
+                break;
+            }
+        }
+
+        None
+    }
+
+    /// Returns the number of children of a vertex. Cancel safe, like `get_children`.
     pub async fn num_children(&self, message_id: &MessageId) -> usize {
         // Effectively atomic
         self.children_inner(message_id)
@@ -366,9 +1940,142 @@ where
             .map_or(0, |approvers| approvers.len())
     }
 
-    #[cfg(test)]
+    /// Returns the number of approvers of a vertex. Unlike `num_children`, approvers fetched from the hooks for a
+    /// vertex that is not in the cache are only counted, without being brought into the cache. Cancel safe, like
+    /// `get_children`.
+    pub async fn get_approvers_count(&self, message_id: &MessageId) -> usize {
+        let cached = self
+            .shard(message_id)
+            .read()
+            .await
+            .get(message_id)
+            .map(|v| Some(v.children().len()).filter(|_| v.children_exhaustive()));
+
+        match cached {
+            Some(Some(count)) => {
+                // Insert cache queue entry to track eviction priority
+                self.cache_queue.lock().await.put(*message_id, ());
+                count
+            }
+            // The vertex is cached, so complete its children while at it
+            Some(None) => self.num_children(message_id).await,
+            None => match self.hooks.fetch_approvers(message_id).await {
+                Err(e) => {
+                    info!("Failed to fetch approvers for message {:?}", e);
+                    0
+                }
+                Ok(approvers) => approvers.map_or(0, |approvers| approvers.len()),
+            },
+        }
+    }
+
+    /// Evicts from the cache all the vertices satisfying `predicate`, and returns how many were evicted. Cancel safe,
+    /// although if dropped part-way only the matching vertices of some of the shards may have been evicted.
+    pub async fn evict_matching<P>(&self, predicate: P) -> usize
+    where
+        P: Fn(&MessageId, &Vertex<T>) -> bool,
+    {
+        let mut evicted = Vec::new();
+        for shard in self.vertices.iter() {
+            let mut vertices = shard.write().await;
+            let matching = vertices
+                .iter()
+                .filter(|(message_id, v)| predicate(message_id, v))
+                .map(|(message_id, _)| *message_id)
+                .collect::<Vec<_>>();
+
+            for message_id in matching {
+                vertices.remove(&message_id);
+                self.cache_len.fetch_sub(1, Ordering::Relaxed);
+                evicted.push(message_id);
+            }
+        }
+
+        let mut cache_queue = self.cache_queue.lock().await;
+        for message_id in evicted.iter() {
+            cache_queue.pop(message_id);
+        }
+
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.evictions.inc_by(evicted.len() as f64);
+            metrics.cache_size.set(self.cache_len.load(Ordering::Relaxed) as i64);
+        }
+
+        evicted.len()
+    }
+
+    /// Removes all the vertices from the cache, leaving the hooks untouched, and returns the messages they held, e.g.
+    /// to export them without copying them. A message is only cloned if it is still referenced elsewhere. Cancel
+    /// safe, although if dropped part-way only the vertices of some of the shards may have been drained.
+    pub async fn drain(&self) -> Vec<(MessageId, Message)> {
+        let mut drained = Vec::new();
+        let mut messages = Vec::new();
+        for shard in self.vertices.iter() {
+            for (message_id, mut vertex) in shard.write().await.drain() {
+                self.cache_len.fetch_sub(1, Ordering::Relaxed);
+                drained.push(message_id);
+
+                if let Some(message) = vertex.take_message() {
+                    messages.push((message_id, message));
+                }
+            }
+        }
+
+        let mut cache_queue = self.cache_queue.lock().await;
+        for message_id in drained.iter() {
+            cache_queue.pop(message_id);
+        }
+
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.cache_size.set(self.cache_len.load(Ordering::Relaxed) as i64);
+        }
+
+        messages
+    }
+
+    /// Panics if a vertex references a parent or a child that is not in the cache. This only holds as long as nothing
+    /// was evicted. Only available with the `test-utils` feature.
+    #[cfg(feature = "test-utils")]
+    pub async fn assert_no_dangling_references(&self) {
+        let mut vertices = HashMap::new();
+        for shard in self.vertices.iter() {
+            for (message_id, v) in shard.read().await.iter() {
+                let parents = v.parents().map(|parents| parents.copied().collect::<Vec<_>>());
+                vertices.insert(*message_id, (parents.unwrap_or_default(), v.children().to_vec()));
+            }
+        }
+
+        for (message_id, (parents, children)) in vertices.iter() {
+            for parent in parents {
+                assert!(
+                    vertices.contains_key(parent),
+                    "Message {:?} references parent {:?} which is not in the tangle",
+                    message_id,
+                    parent
+                );
+            }
+            for child in children {
+                assert!(
+                    vertices.contains_key(child),
+                    "Message {:?} references child {:?} which is not in the tangle",
+                    message_id,
+                    child
+                );
+            }
+        }
+    }
+
+    /// Removes all the vertices from the cache, leaving the hooks untouched. Only available with the `test-utils`
+    /// feature.
+    #[cfg(feature = "test-utils")]
     pub async fn clear(&mut self) {
-        self.vertices.write().await.clear();
+        for shard in self.vertices.iter() {
+            shard.write().await.clear();
+        }
+        self.cache_queue.lock().await.clear();
+        self.cache_len.store(0, Ordering::Relaxed);
     }
 
     // Attempts to pull the message from the storage, returns true if successful.
@@ -379,7 +2086,19 @@ where
             self.cache_queue.lock().await.put(*message_id, ());
 
             true
+        } else if let Some((tx, metadata)) = self.dirty_entry(message_id).await {
+            // The hooks hold an outdated copy of dirty vertices that got evicted before being flushed
+            self.insert_inner(*message_id, tx, metadata).await;
+
+            true
+        } else if self.hooks.is_noop() {
+            false
         } else if let Ok(Some((tx, metadata))) = self.hooks.get(message_id).await {
+            #[cfg(feature = "metrics")]
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.hook_hits.inc();
+            }
+
             // Insert cache queue entry to track eviction priority
             self.cache_queue.lock().await.put(*message_id, ());
 
@@ -387,26 +2106,66 @@ where
 
             true
         } else {
+            #[cfg(feature = "metrics")]
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.hook_misses.inc();
+            }
+
             false
         }
     }
 
-    async fn perform_eviction(&self) {
+    // Evicts vertices if the cache exceeds its capacity, and returns how many were evicted.
+    async fn perform_eviction(&self) -> usize {
         let max_len = self.max_len.load(Ordering::Relaxed);
-        let len = self.vertices.read().await.len();
+
+        // Cheap check first, so that the shards are only all locked when eviction is likely needed
+        if self.cache_len.load(Ordering::Relaxed) <= max_len {
+            #[cfg(feature = "metrics")]
+            if let Some(metrics) = self.metrics.as_ref() {
+                metrics.cache_size.set(self.cache_len.load(Ordering::Relaxed) as i64);
+            }
+
+            return 0;
+        }
+
+        // Check the length again under the write locks so that concurrent insertions can't change it before eviction.
+        // The shards are always locked in ascending order.
+        let mut shards = Vec::with_capacity(self.vertices.len());
+        for shard in self.vertices.iter() {
+            shards.push(shard.write().await);
+        }
+        let mut len = shards.iter().map(|vertices| vertices.len()).sum::<usize>();
+        let mut evicted = 0;
+
         if len > max_len {
-            let mut vertices = self.vertices.write().await;
             let mut cache_queue = self.cache_queue.lock().await;
-            while vertices.len() > ((1.0 - CACHE_THRESHOLD_FACTOR) * max_len as f64) as usize {
+            while len > ((1.0 - CACHE_THRESHOLD_FACTOR) * max_len as f64) as usize {
                 let remove = cache_queue.pop_lru().map(|(id, _)| id);
 
                 if let Some(message_id) = remove {
-                    vertices.remove(&message_id);
+                    if shards[self.shard_index(&message_id)].remove(&message_id).is_some() {
+                        self.cache_len.fetch_sub(1, Ordering::Relaxed);
+                        len -= 1;
+                        evicted += 1;
+
+                        #[cfg(feature = "metrics")]
+                        if let Some(metrics) = self.metrics.as_ref() {
+                            metrics.evictions.inc();
+                        }
+                    }
                 } else {
                     break;
                 }
             }
         }
+
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.cache_size.set(len as i64);
+        }
+
+        evicted
     }
 }
 
@@ -95,10 +95,13 @@ pub async fn visit_parents_depth_first<Fut, Metadata, Match, Apply, ElseApply, M
 {
     let mut parents = Vec::new();
     let mut visited = HashSet::new();
+    let mut steps = 0;
 
     parents.push(root);
 
     while let Some(message_id) = parents.pop() {
+        tangle.yield_every(&mut steps).await;
+
         if !visited.contains(&message_id) {
             let msg_meta = tangle
                 .get_vertex(&message_id)
@@ -9,7 +9,7 @@ use hashbrown::{hash_map::Entry, HashMap, HashSet};
 use log::debug;
 use rand::seq::IteratorRandom;
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 enum Score {
     NonLazy,
@@ -36,15 +36,23 @@ const MAX_AGE_SECONDS_AFTER_FIRST_CHILD: u8 = 3;
 // used to widen the cone of the tangle.
 const MAX_NUM_CHILDREN: u8 = 2;
 
-#[derive(Default)]
 struct TipMetadata {
     children: HashSet<MessageId>,
     time_first_child: Option<Instant>,
+    time_inserted: Instant,
 }
 
 impl TipMetadata {
     pub(crate) fn new() -> Self {
-        Self::default()
+        Self {
+            children: HashSet::new(),
+            time_first_child: None,
+            time_inserted: Instant::now(),
+        }
+    }
+
+    pub(crate) fn age(&self) -> Duration {
+        self.time_inserted.elapsed()
     }
 }
 
@@ -174,6 +182,17 @@ impl UrtsTipPool {
         }
     }
 
+    pub(crate) fn count_orphans(&self, max_age: Duration) -> usize {
+        self.tips.values().filter(|metadata| metadata.age() > max_age).count()
+    }
+
+    pub(crate) fn youngest_tip(&self) -> Option<(MessageId, Instant)> {
+        self.tips
+            .iter()
+            .max_by_key(|(_, metadata)| metadata.time_inserted)
+            .map(|(message_id, metadata)| (*message_id, metadata.time_inserted))
+    }
+
     pub(crate) fn optimal_num_tips(&self) -> usize {
         // TODO: hardcoded at the moment
         4
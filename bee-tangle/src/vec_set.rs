@@ -1,16 +1,23 @@
 // Copyright 2020 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{cmp::Eq, ops::Deref};
+use hashbrown::HashSet;
 
+use std::{cmp::Eq, hash::Hash, ops::Deref};
+
+/// An insertion-ordered set, backed by both a `Vec` for ordered access and a `HashSet` for O(1) membership checks.
 #[derive(Clone)]
 pub struct VecSet<T> {
     items: Vec<T>,
+    index: HashSet<T>,
 }
 
 impl<T> Default for VecSet<T> {
     fn default() -> Self {
-        Self { items: Vec::default() }
+        Self {
+            items: Vec::default(),
+            index: HashSet::default(),
+        }
     }
 }
 
@@ -21,15 +28,22 @@ impl<T> VecSet<T> {
 
     pub fn insert(&mut self, item: T) -> bool
     where
-        T: Eq,
+        T: Eq + Hash + Clone,
     {
-        if self.items.contains(&item) {
-            false
-        } else {
+        if self.index.insert(item.clone()) {
             self.items.push(item);
             true
+        } else {
+            false
         }
     }
+
+    pub fn contains(&self, item: &T) -> bool
+    where
+        T: Eq + Hash,
+    {
+        self.index.contains(item)
+    }
 }
 
 impl<T> Deref for VecSet<T> {
@@ -5,7 +5,21 @@ use crate::{MessageRef, VecSet};
 
 use bee_message::{Message, MessageId};
 
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+// Revisions are drawn from a single counter so that they keep increasing even when a vertex is evicted and fetched
+// again.
+static NEXT_CHILD_REVISION: AtomicU64 = AtomicU64::new(1);
+// Likewise, metadata versions are drawn from a single counter so that a vertex fetched again after being evicted never
+// reuses a version handed out before.
+static NEXT_METADATA_VERSION: AtomicU64 = AtomicU64::new(1);
+
+fn next_metadata_version() -> u64 {
+    NEXT_METADATA_VERSION.fetch_add(1, Ordering::Relaxed)
+}
 
 #[derive(Clone)]
 pub struct Vertex<T>
@@ -14,6 +28,10 @@ where
 {
     message: Option<(MessageRef, T)>,
     children: (VecSet<MessageId>, bool), // Exhaustive flag
+    // The revision at which each child was added, in the same order as the children
+    children_revisions: Vec<u64>,
+    metadata_version: u64,
+    past_cone_complete: bool,
 }
 
 impl<T> Vertex<T>
@@ -24,6 +42,9 @@ where
         Self {
             message: None,
             children: (VecSet::default(), false),
+            children_revisions: Vec::new(),
+            metadata_version: 0,
+            past_cone_complete: false,
         }
     }
 
@@ -31,6 +52,9 @@ where
         Self {
             message: Some((MessageRef(Arc::new(message)), metadata)),
             children: (VecSet::default(), false),
+            children_revisions: Vec::new(),
+            metadata_version: next_metadata_version(),
+            past_cone_complete: false,
         }
     }
 
@@ -50,22 +74,89 @@ where
         self.message_and_metadata().map(|(_, m)| m)
     }
 
+    /// Gives mutable access to the metadata. Writes made through it don't bump the version of the metadata, unlike
+    /// `update_metadata`.
     pub fn metadata_mut(&mut self) -> Option<&mut T> {
         self.message.as_mut().map(|(_, m)| m)
     }
 
+    /// Updates the metadata, and returns the result of the update along with whether the metadata changed. The version
+    /// of the metadata is only bumped if it changed.
+    pub fn update_metadata<R, Update>(&mut self, update: Update) -> Option<(R, bool)>
+    where
+        T: PartialEq,
+        Update: FnOnce(&mut T) -> R,
+    {
+        let metadata_version = &mut self.metadata_version;
+        self.message.as_mut().map(|(_, m)| {
+            let previous = m.clone();
+            let r = update(m);
+            let changed = *m != previous;
+            if changed {
+                *metadata_version = next_metadata_version();
+            }
+            (r, changed)
+        })
+    }
+
+    /// Moves the message out of the vertex, leaving it without message nor metadata. The message is only cloned if it
+    /// is still referenced elsewhere.
+    pub fn take_message(&mut self) -> Option<Message> {
+        self.message
+            .take()
+            .map(|(message, _)| Arc::try_unwrap(message.0).unwrap_or_else(|message| (*message).clone()))
+    }
+
+    /// Returns the version of the metadata, which changes every time the metadata is updated to a different value and
+    /// is never reused, even by a vertex fetched again after being evicted.
+    pub fn metadata_version(&self) -> u64 {
+        self.metadata_version
+    }
+
     pub fn add_child(&mut self, child: MessageId) {
-        self.children.0.insert(child);
+        if self.children.0.insert(child) {
+            self.children_revisions
+                .push(NEXT_CHILD_REVISION.fetch_add(1, Ordering::Relaxed));
+        }
     }
 
     pub fn children(&self) -> &[MessageId] {
         &self.children.0
     }
 
+    /// Returns the children added after the given revision, along with the revision to pass to get the next ones.
+    pub fn children_since(&self, revision: u64) -> (Vec<MessageId>, u64) {
+        // Revisions are unique and increasing
+        let since = match self.children_revisions.binary_search(&revision) {
+            Ok(index) => index + 1,
+            Err(index) => index,
+        };
+        let current = self
+            .children_revisions
+            .last()
+            .map_or(revision, |last| revision.max(*last));
+
+        (self.children.0[since..].to_vec(), current)
+    }
+
+    pub fn children_contains(&self, child: &MessageId) -> bool {
+        self.children.0.contains(child)
+    }
+
     pub fn children_exhaustive(&self) -> bool {
         self.children.1
     }
 
+    /// Returns whether the whole past cone of this vertex is known to be available, as set on solidification. The flag
+    /// only lives in the cache and is lost when the vertex is evicted, so it may be missing for solid messages.
+    pub fn has_complete_past_cone(&self) -> bool {
+        self.past_cone_complete
+    }
+
+    pub(crate) fn set_past_cone_complete(&mut self) {
+        self.past_cone_complete = true;
+    }
+
     /// Set the exhaustive flag. This should not be done if the vertex's children are exhaustive.
     pub(crate) fn set_exhaustive(&mut self) {
         self.children.1 = true;
@@ -73,6 +164,70 @@ where
 
     pub(crate) fn insert_message_and_metadata(&mut self, msg: Message, meta: T) {
         self.message = Some((MessageRef(Arc::new(msg)), meta));
+        self.metadata_version = next_metadata_version();
+    }
+}
+
+/// An owned copy of a vertex, that doesn't hold any lock on the tangle.
+#[derive(Clone)]
+pub struct VertexSnapshot<T> {
+    /// The message of the vertex, if known.
+    pub message: Option<MessageRef>,
+    /// The metadata of the vertex, if known.
+    pub metadata: Option<T>,
+    /// The version of the metadata.
+    pub metadata_version: u64,
+    /// The children of the vertex known to the cache.
+    pub children: Vec<MessageId>,
+    /// Whether the children are exhaustively known by the cache.
+    pub children_exhaustive: bool,
+    /// Whether the whole past cone of the vertex is known to be available.
+    pub past_cone_complete: bool,
+}
+
+impl<T> From<&Vertex<T>> for VertexSnapshot<T>
+where
+    T: Clone,
+{
+    fn from(vertex: &Vertex<T>) -> Self {
+        Self {
+            message: vertex.message().cloned(),
+            metadata: vertex.metadata().cloned(),
+            metadata_version: vertex.metadata_version(),
+            children: vertex.children().to_vec(),
+            children_exhaustive: vertex.children_exhaustive(),
+            past_cone_complete: vertex.has_complete_past_cone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bee_common::packable::Packable;
+    use bee_test::rand::message::rand_message;
+
+    #[test]
+    fn take_message() {
+        let message = rand_message();
+        let mut vertex = Vertex::new(message.clone(), 0u64);
+
+        assert_eq!(vertex.take_message().unwrap().pack_new(), message.pack_new());
+        assert!(vertex.message().is_none());
+        assert!(vertex.metadata().is_none());
+        assert!(vertex.take_message().is_none());
+    }
+
+    #[test]
+    fn take_shared_message() {
+        let message = rand_message();
+        let mut vertex = Vertex::new(message.clone(), 0u64);
+        let shared = vertex.message().cloned().unwrap();
+
+        // The message is still referenced, so it is cloned
+        assert_eq!(vertex.take_message().unwrap().pack_new(), message.pack_new());
+        assert_eq!(shared.pack_new(), message.pack_new());
     }
 }
 
@@ -0,0 +1,418 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A write-ahead log providing crash recovery for hooks that are not themselves durable.
+
+use crate::{sync, Hooks, Tangle};
+
+use bee_common::packable::Packable;
+use bee_message::{Message, MessageId, MESSAGE_ID_LENGTH};
+
+use async_trait::async_trait;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::TryInto,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+const INSERT_RECORD: u8 = 0;
+const COMMIT_RECORD: u8 = 1;
+
+type Entry<T> = (MessageId, Message, T);
+
+/// An error raised by a `WalManager`.
+#[derive(Debug)]
+pub enum WalError<E, M> {
+    /// Accessing the log failed.
+    Io(io::Error),
+    /// A message of the log could not be unpacked.
+    Message(bee_message::Error),
+    /// A metadata of the log could not be packed or unpacked.
+    Metadata(M),
+    /// The wrapped hooks failed.
+    Hooks(E),
+}
+
+impl<E, M> From<io::Error> for WalError<E, M> {
+    fn from(error: io::Error) -> Self {
+        WalError::Io(error)
+    }
+}
+
+// The log file, along with the bodies of its insert records without a matching commit record.
+struct Log {
+    file: File,
+    pending: BTreeMap<u64, Vec<u8>>,
+    // The pending entries currently being handed over to the wrapped hooks
+    in_flight: BTreeSet<u64>,
+}
+
+// Marks an entry as no longer in flight when dropped.
+struct Release<'a, H>(&'a WalManager<H>, u64);
+
+impl<H> Drop for Release<'_, H> {
+    fn drop(&mut self) {
+        self.0.release(Some(self.1));
+    }
+}
+
+/// Hooks wrapping other hooks, and appending every insertion to a log before handing it over to them. Insertions that
+/// did not reach the wrapped hooks, because they failed or because of a crash, are recovered with `replay`.
+pub struct WalManager<H> {
+    hooks: H,
+    path: PathBuf,
+    log: Arc<Mutex<Log>>,
+    sequence: AtomicU64,
+}
+
+impl<H> WalManager<H> {
+    /// Opens or creates the log at `path`, wrapping the given hooks. The uncommitted entries of an existing log are
+    /// kept until they are replayed.
+    pub fn open<P: AsRef<Path>>(path: P, hooks: H) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new().read(true).append(true).create(true).open(&path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        let pending = read_pending(&bytes);
+        let sequence = pending.keys().next_back().map_or(0, |sequence| sequence + 1);
+
+        Ok(Self {
+            hooks,
+            path,
+            log: Arc::new(Mutex::new(Log {
+                file,
+                pending,
+                in_flight: BTreeSet::new(),
+            })),
+            sequence: AtomicU64::new(sequence),
+        })
+    }
+
+    /// Returns the wrapped hooks.
+    pub fn inner(&self) -> &H {
+        &self.hooks
+    }
+
+    /// Rewrites the log with only its uncommitted entries, including the ones in flight or that failed to reach the
+    /// wrapped hooks. The new log is written aside and then moved over the old one, so that a crash in the middle
+    /// leaves either of them whole. Returns the number of entries kept.
+    pub async fn compact(&self) -> io::Result<usize> {
+        let path = self.path.clone();
+
+        self.with_log(move |log| {
+            let compacted = path.with_extension("compact");
+            let mut file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&compacted)?;
+
+            for (sequence, body) in log.pending.iter() {
+                file.write_all(&insert_record(*sequence, body))?;
+            }
+            file.sync_data()?;
+            fs::rename(&compacted, &path)?;
+            // The rename is only durable once the directory is synced
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                File::open(dir)?.sync_all()?;
+            }
+
+            // Records are only ever appended, so the file can keep being written from its end
+            log.file = file;
+
+            Ok(log.pending.len())
+        })
+        .await
+    }
+
+    // Runs `f` on the log on a blocking thread, as it does blocking file operations.
+    async fn with_log<R, F>(&self, f: F) -> io::Result<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Log) -> io::Result<R> + Send + 'static,
+    {
+        let log = self.log.clone();
+
+        // Unwrap is fine because the lock is never held across a panicking operation.
+        sync::spawn_blocking(move || f(&mut log.lock().unwrap()))
+            .await
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+    }
+
+    // Durably appends an insert record, marking its entry as pending and in flight.
+    async fn append(&self, sequence: u64, body: Vec<u8>) -> io::Result<()> {
+        self.with_log(move |log| {
+            log.file.write_all(&insert_record(sequence, &body))?;
+            log.file.sync_data()?;
+            log.pending.insert(sequence, body);
+            log.in_flight.insert(sequence);
+
+            Ok(())
+        })
+        .await
+    }
+
+    // Appends a commit record. The entry no longer needs to be kept once the record is written, but it does not need
+    // to be durable either: at worst, the entry is replayed again after a crash.
+    async fn commit(&self, sequence: u64) -> io::Result<()> {
+        self.with_log(move |log| {
+            log.file.write_all(&commit_record(sequence))?;
+            log.pending.remove(&sequence);
+            log.in_flight.remove(&sequence);
+
+            Ok(())
+        })
+        .await
+    }
+
+    // Marks the given entries as no longer in flight, leaving them pending if they were not committed.
+    fn release(&self, sequences: impl IntoIterator<Item = u64>) {
+        // Unwrap is fine because the lock is never held across a panicking operation.
+        let mut log = self.log.lock().unwrap();
+
+        for sequence in sequences {
+            log.in_flight.remove(&sequence);
+        }
+    }
+
+    /// Replays the uncommitted entries of the log that are not in flight into the wrapped hooks, and brings them back
+    /// into the tangle. Returns the number of replayed entries. Entries that fail to be replayed, or that were not
+    /// reached because of a failure, stay in the log to be replayed later.
+    pub async fn replay<T>(tangle: &Tangle<T, Self>) -> Result<usize, WalError<H::Error, T::Error>>
+    where
+        T: Clone + Packable + Send + Sync + 'static,
+        H: Hooks<T> + Send + Sync,
+    {
+        let wal = tangle.hooks();
+        let pending = {
+            // Unwrap is fine because the lock is never held across a panicking operation.
+            let mut log = wal.log.lock().unwrap();
+            let Log { pending, in_flight, .. } = &mut *log;
+            let pending = pending
+                .iter()
+                .filter(|(sequence, _)| !in_flight.contains(sequence))
+                .map(|(sequence, body)| (*sequence, body.clone()))
+                .collect::<Vec<_>>();
+            in_flight.extend(pending.iter().map(|(sequence, _)| *sequence));
+            pending
+        };
+        let mut replayed = 0;
+
+        for (index, (sequence, body)) in pending.iter().enumerate() {
+            let res = async {
+                let (message_id, message, metadata) = decode_entry::<T, H::Error>(body)?;
+
+                for &parent in message.parents().iter() {
+                    wal.hooks
+                        .insert_approver(parent, message_id)
+                        .await
+                        .map_err(WalError::Hooks)?;
+                }
+                wal.hooks
+                    .insert(message_id, message, metadata)
+                    .await
+                    .map_err(WalError::Hooks)?;
+                wal.commit(*sequence).await?;
+
+                Ok::<_, WalError<H::Error, T::Error>>(message_id)
+            }
+            .await;
+
+            let message_id = match res {
+                Ok(message_id) => message_id,
+                Err(e) => {
+                    wal.release(pending[index..].iter().map(|(sequence, _)| *sequence));
+                    return Err(e);
+                }
+            };
+
+            // Pull the message back into the cache
+            tangle.get(&message_id).await;
+
+            replayed += 1;
+        }
+
+        Ok(replayed)
+    }
+}
+
+#[async_trait]
+impl<T, H> Hooks<T> for WalManager<H>
+where
+    T: Packable + Send + Sync + 'static,
+    H: Hooks<T> + Send + Sync,
+{
+    type Error = WalError<H::Error, T::Error>;
+
+    // Nothing is written to hooks doing nothing, so there is nothing to log ahead of them either
+    fn is_noop(&self) -> bool {
+        self.hooks.is_noop()
+    }
+
+    async fn get(&self, message_id: &MessageId) -> Result<Option<(Message, T)>, Self::Error> {
+        self.hooks.get(message_id).await.map_err(WalError::Hooks)
+    }
+
+    async fn insert(&self, message_id: MessageId, tx: Message, metadata: T) -> Result<(), Self::Error> {
+        let sequence = self.sequence.fetch_add(1, Ordering::Relaxed);
+
+        let mut body = Vec::new();
+        encode_entry(&mut body, &message_id, &tx, &metadata)?;
+
+        self.append(sequence, body).await?;
+        // A failed or cancelled entry stays pending, to be replayed
+        let _release = Release(self, sequence);
+        self.hooks
+            .insert(message_id, tx, metadata)
+            .await
+            .map_err(WalError::Hooks)?;
+
+        Ok(self.commit(sequence).await?)
+    }
+
+    async fn fetch_approvers(&self, message_id: &MessageId) -> Result<Option<Vec<MessageId>>, Self::Error> {
+        self.hooks.fetch_approvers(message_id).await.map_err(WalError::Hooks)
+    }
+
+    async fn fetch_approvers_batch(
+        &self,
+        message_ids: &[MessageId],
+    ) -> Result<Vec<Option<Vec<MessageId>>>, Self::Error> {
+        self.hooks
+            .fetch_approvers_batch(message_ids)
+            .await
+            .map_err(WalError::Hooks)
+    }
+
+    async fn insert_approver(&self, message_id: MessageId, approver: MessageId) -> Result<(), Self::Error> {
+        self.hooks
+            .insert_approver(message_id, approver)
+            .await
+            .map_err(WalError::Hooks)
+    }
+
+    async fn update_approvers(&self, message_id: MessageId, approvers: &[MessageId]) -> Result<(), Self::Error> {
+        self.hooks
+            .update_approvers(message_id, approvers)
+            .await
+            .map_err(WalError::Hooks)
+    }
+
+    async fn count(&self) -> Result<usize, Self::Error> {
+        self.hooks.count().await.map_err(WalError::Hooks)
+    }
+}
+
+fn insert_record(sequence: u64, body: &[u8]) -> Vec<u8> {
+    let mut record = vec![INSERT_RECORD];
+    record.extend_from_slice(&sequence.to_le_bytes());
+    record.extend_from_slice(body);
+    record
+}
+
+fn commit_record(sequence: u64) -> Vec<u8> {
+    let mut record = vec![COMMIT_RECORD];
+    record.extend_from_slice(&sequence.to_le_bytes());
+    record
+}
+
+// An entry is laid out as the message id, followed by the length-prefixed message and metadata.
+fn encode_entry<T: Packable, E>(
+    buf: &mut Vec<u8>,
+    message_id: &MessageId,
+    message: &Message,
+    metadata: &T,
+) -> Result<(), WalError<E, T::Error>> {
+    buf.extend_from_slice(message_id.as_ref());
+
+    let message = message.pack_new();
+    buf.extend_from_slice(&(message.len() as u32).to_le_bytes());
+    buf.extend_from_slice(&message);
+
+    let mut packed = Vec::with_capacity(metadata.packed_len());
+    metadata.pack(&mut packed).map_err(WalError::Metadata)?;
+    buf.extend_from_slice(&(packed.len() as u32).to_le_bytes());
+    buf.extend_from_slice(&packed);
+
+    Ok(())
+}
+
+fn decode_entry<T: Packable, E>(mut bytes: &[u8]) -> Result<Entry<T>, WalError<E, T::Error>> {
+    // Unwrap is fine because the slice has exactly the length of a message id.
+    let message_id = MessageId::new(read_slice(&mut bytes, MESSAGE_ID_LENGTH)?.try_into().unwrap());
+
+    let len = read_u32(&mut bytes)? as usize;
+    let message = Message::unpack(&mut read_slice(&mut bytes, len)?).map_err(WalError::Message)?;
+
+    let len = read_u32(&mut bytes)? as usize;
+    let metadata = T::unpack(&mut read_slice(&mut bytes, len)?).map_err(WalError::Metadata)?;
+
+    Ok((message_id, message, metadata))
+}
+
+// Returns the bodies of the insert records without a matching commit record, by sequence number. A truncated record at
+// the end of the log, left by a crash while appending it, is ignored.
+fn read_pending(mut bytes: &[u8]) -> BTreeMap<u64, Vec<u8>> {
+    let mut pending = BTreeMap::new();
+
+    while let Some((&kind, rest)) = bytes.split_first() {
+        bytes = rest;
+
+        let sequence = match read_slice(&mut bytes, std::mem::size_of::<u64>()) {
+            // Unwrap is fine because the slice has exactly the length of a `u64`.
+            Ok(sequence) => u64::from_le_bytes(sequence.try_into().unwrap()),
+            Err(_) => break,
+        };
+
+        match kind {
+            INSERT_RECORD => match entry_len(bytes).and_then(|len| read_slice(&mut bytes, len)) {
+                Ok(body) => {
+                    pending.insert(sequence, body.to_vec());
+                }
+                Err(_) => break,
+            },
+            COMMIT_RECORD => {
+                pending.remove(&sequence);
+            }
+            _ => break,
+        }
+    }
+
+    pending
+}
+
+fn entry_len(bytes: &[u8]) -> io::Result<usize> {
+    let mut cursor = bytes;
+
+    read_slice(&mut cursor, MESSAGE_ID_LENGTH)?;
+    let message_len = read_u32(&mut cursor)? as usize;
+    read_slice(&mut cursor, message_len)?;
+    let metadata_len = read_u32(&mut cursor)? as usize;
+    read_slice(&mut cursor, metadata_len)?;
+
+    Ok(bytes.len() - cursor.len())
+}
+
+fn read_slice<'a>(bytes: &mut &'a [u8], len: usize) -> io::Result<&'a [u8]> {
+    if bytes.len() < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    let (slice, rest) = bytes.split_at(len);
+    *bytes = rest;
+
+    Ok(slice)
+}
+
+fn read_u32(bytes: &mut &[u8]) -> io::Result<u32> {
+    // Unwrap is fine because the slice has exactly the length of a `u32`.
+    read_slice(bytes, std::mem::size_of::<u32>()).map(|slice| u32::from_le_bytes(slice.try_into().unwrap()))
+}
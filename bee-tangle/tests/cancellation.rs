@@ -0,0 +1,45 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_message::{MessageId, Parents};
+use bee_tangle::{ShardedTangle, Tangle};
+use bee_test::rand::{
+    bytes::rand_bytes_32,
+    message::{rand_message, rand_message_with_parents},
+};
+
+use futures::FutureExt;
+
+// Returns a random id falling in the given shard of a tangle with two shards.
+fn rand_message_id_in_shard(shard: u8) -> MessageId {
+    let mut bytes = rand_bytes_32();
+    bytes[0] = (bytes[0] & !1) | shard;
+    MessageId::new(bytes)
+}
+
+#[tokio::test]
+async fn dropped_insertion_leaves_no_trace() {
+    let tangle: ShardedTangle<u64> = Tangle::default().with_shards(2);
+
+    let parent = rand_message_id_in_shard(1);
+    tangle.insert(parent, rand_message(), 0).await;
+    let len = tangle.len().await;
+
+    let message_id = rand_message_id_in_shard(0);
+    let message = rand_message_with_parents(Parents::new(vec![parent]).unwrap());
+
+    {
+        // The insertion locks the shard of the message first, then waits for the read-locked shard of its parent
+        let children = tangle.children_guard(&parent).await.unwrap();
+        assert!(children.is_empty());
+        assert!(tangle.insert(message_id, message.clone(), 0).now_or_never().is_none());
+    }
+
+    assert!(!tangle.contains(&message_id).await);
+    assert_eq!(tangle.get_children(&parent).await, Some(Vec::new()));
+    assert_eq!(tangle.len().await, len);
+
+    assert!(tangle.insert(message_id, message, 0).await.is_some());
+    assert_eq!(tangle.get_children(&parent).await, Some(vec![message_id]));
+    assert_eq!(tangle.len().await, len + 1);
+}
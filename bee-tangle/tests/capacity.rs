@@ -0,0 +1,26 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_tangle::InMemoryTangle;
+
+#[tokio::test]
+async fn capacity_follows_resizes() {
+    let tangle = InMemoryTangle::<u64>::default().with_capacity(10);
+    assert_eq!(tangle.capacity(), 10);
+
+    tangle.resize(20);
+    assert_eq!(tangle.capacity(), 20);
+    assert_eq!(tangle.stats().await.capacity, 20);
+
+    tangle.resize_and_evict(5).await;
+    assert_eq!(tangle.capacity(), 5);
+}
+
+#[tokio::test]
+async fn zero_capacity_is_clamped() {
+    let tangle = InMemoryTangle::<u64>::default().with_capacity(0);
+    assert_eq!(tangle.capacity(), 1);
+
+    tangle.resize(0);
+    assert_eq!(tangle.capacity(), 1);
+}
@@ -0,0 +1,317 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{message_with_parents, MemoryHooks};
+
+use bee_tangle::Tangle;
+use bee_test::rand::message::{rand_message_id, rand_message_ids};
+
+use futures::stream;
+
+#[tokio::test]
+async fn num_children_batch_from_cache_and_hooks() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default()).with_shards(4);
+    let parents = rand_message_ids(8);
+
+    // Children of the first half of the parents are known by the cache, the others only by the hooks
+    for (index, parent) in parents.iter().enumerate() {
+        for _ in 0..index {
+            let (message_id, message) = message_with_parents(&[*parent]);
+            if index < 4 {
+                tangle.insert(message_id, message, 0).await;
+                tangle.get_children(parent).await;
+            } else {
+                tangle
+                    .hooks()
+                    .approvers
+                    .lock()
+                    .unwrap()
+                    .entry(*parent)
+                    .or_default()
+                    .push(message_id);
+            }
+        }
+    }
+
+    let mut message_ids = parents.clone();
+    message_ids.push(rand_message_id());
+
+    assert_eq!(
+        tangle.num_children_batch(&message_ids).await,
+        vec![0, 1, 2, 3, 4, 5, 6, 7, 0]
+    );
+
+    // The children fetched from the hooks are now known by the cache
+    for (index, parent) in parents.iter().enumerate() {
+        assert_eq!(tangle.get_children_cached(parent).await.map(|c| c.len()), Some(index));
+    }
+}
+
+#[tokio::test]
+async fn children_since_revision_survives_eviction() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let parent = rand_message_id();
+    let (first, message) = message_with_parents(&[parent]);
+    tangle.insert(first, message, 0).await;
+    let (second, message) = message_with_parents(&[parent]);
+    tangle.insert(second, message, 0).await;
+
+    let (children, revision) = tangle.children_since(&parent, 0).await.unwrap();
+    assert_eq!(children, vec![first, second]);
+    assert_eq!(
+        tangle.children_since(&parent, revision).await,
+        Some((Vec::new(), revision))
+    );
+
+    let (third, message) = message_with_parents(&[parent]);
+    tangle.insert(third, message, 0).await;
+
+    let (children, new_revision) = tangle.children_since(&parent, revision).await.unwrap();
+    assert_eq!(children, vec![third]);
+    assert!(new_revision > revision);
+    let revision = new_revision;
+
+    // Evict the parent, whose children are then fetched again from the hooks
+    tangle.get(&third).await;
+    tangle.resize_and_evict(1).await;
+    assert_eq!(tangle.get_children_cached(&parent).await, None);
+
+    let (mut children, new_revision) = tangle.children_since(&parent, revision).await.unwrap();
+    let mut expected = vec![first, second, third];
+    children.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+    expected.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+    assert_eq!(children, expected);
+    assert!(new_revision > revision);
+}
+
+#[tokio::test]
+async fn get_children_cached_only_reads_exhaustive_children() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let parent = rand_message_id();
+    let (first, message) = message_with_parents(&[parent]);
+    tangle.insert(first, message, 0).await;
+
+    // The hooks may know about other children, so the children of the cache alone are not enough
+    assert_eq!(tangle.get_children_cached(&parent).await, None);
+    assert_eq!(tangle.get_children_cached(&rand_message_id()).await, None);
+
+    tangle
+        .hooks()
+        .approvers
+        .lock()
+        .unwrap()
+        .entry(parent)
+        .or_default()
+        .push(first);
+    assert_eq!(tangle.get_children(&parent).await, Some(vec![first]));
+    assert_eq!(tangle.get_children_cached(&parent).await, Some(vec![first]));
+
+    // Children inserted afterwards are known by the cache
+    let (second, message) = message_with_parents(&[parent]);
+    tangle.insert(second, message, 0).await;
+    assert_eq!(tangle.get_children_cached(&parent).await, Some(vec![first, second]));
+}
+
+#[tokio::test]
+async fn get_children_count_from_cache_and_hooks() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let parent = rand_message_id();
+
+    // Children only known by the hooks are fetched
+    tangle
+        .hooks()
+        .approvers
+        .lock()
+        .unwrap()
+        .insert(parent, rand_message_ids(3));
+    assert_eq!(tangle.get_children_count(&parent).await, 3);
+
+    // Children are now exhaustively known by the cache
+    let (message_id, message) = message_with_parents(&[parent]);
+    tangle.insert(message_id, message, 0).await;
+    assert_eq!(tangle.get_children_count(&parent).await, 4);
+
+    assert_eq!(tangle.get_children_count(&rand_message_id()).await, 0);
+}
+
+#[tokio::test]
+async fn edges_of_the_cache() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default()).with_shards(4);
+    let parents = rand_message_ids(2);
+    let (child, message) = message_with_parents(&parents);
+    tangle.insert(child, message, 0).await;
+    let (grandchild, message) = message_with_parents(&[child]);
+    tangle.insert(grandchild, message, 0).await;
+
+    let mut edges = tangle.edges().await;
+    edges.sort_by(|a, b| (a.0.as_ref(), a.1.as_ref()).cmp(&(b.0.as_ref(), b.1.as_ref())));
+
+    let mut expected = vec![(parents[0], child), (parents[1], child), (child, grandchild)];
+    expected.sort_by(|a, b| (a.0.as_ref(), a.1.as_ref()).cmp(&(b.0.as_ref(), b.1.as_ref())));
+
+    assert_eq!(edges, expected);
+}
+
+#[tokio::test]
+async fn children_are_deduplicated() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let parent = rand_message_id();
+    let (child, message) = message_with_parents(&[parent]);
+    tangle.insert(child, message, 0).await;
+
+    // The hooks know about the child as well, so it is fetched again along with the others
+    let other = rand_message_id();
+    tangle
+        .hooks()
+        .approvers
+        .lock()
+        .unwrap()
+        .get_mut(&parent)
+        .unwrap()
+        .extend(vec![child, other]);
+
+    assert_eq!(tangle.get_children(&parent).await, Some(vec![child, other]));
+
+    let vertex = tangle.get_vertex(&parent).await.unwrap();
+    assert!(vertex.children_contains(&child));
+    assert!(vertex.children_contains(&other));
+    assert!(!vertex.children_contains(&rand_message_id()));
+}
+
+#[tokio::test]
+async fn import_vertex_with_exhaustive_children() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let parent = rand_message_id();
+    let (message_id, message) = message_with_parents(&[parent]);
+    let children = rand_message_ids(2);
+
+    tangle.import_vertex(message_id, message, 4, children.clone()).await;
+
+    // The children are trusted as is, without asking the hooks
+    assert_eq!(tangle.get_children_cached(&message_id).await, Some(children));
+    assert_eq!(tangle.get_metadata(&message_id).await, Some(4));
+
+    // Neither child edges are derived from the parents, nor are the hooks called
+    assert_eq!(tangle.get_children_cached(&parent).await, None);
+    assert!(!tangle.hooks().contains(&message_id));
+    assert!(tangle.hooks().approvers.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn num_children_batch_records_fetched_children() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default()).with_shards(2);
+    let parents = rand_message_ids(3);
+    for (index, parent) in parents.iter().enumerate() {
+        tangle
+            .hooks()
+            .approvers
+            .lock()
+            .unwrap()
+            .insert(*parent, rand_message_ids(index));
+    }
+
+    assert_eq!(tangle.num_children_batch(&parents).await, vec![0, 1, 2]);
+
+    // The fetched children are now exhaustively known by the cache, so the hooks are not needed anymore
+    tangle.hooks().approvers.lock().unwrap().clear();
+    for (index, parent) in parents.iter().enumerate() {
+        assert_eq!(
+            tangle.get_children_cached(parent).await.map(|children| children.len()),
+            Some(index)
+        );
+    }
+}
+
+#[tokio::test]
+async fn get_children_sorted_ignores_insertion_order() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let parent = rand_message_id();
+    let mut children = Vec::new();
+    for _ in 0..4 {
+        let (message_id, message) = message_with_parents(&[parent]);
+        tangle.insert(message_id, message, 0).await;
+        children.push(message_id);
+    }
+    children.sort();
+
+    assert_eq!(tangle.get_children_sorted(&parent).await, Some(children));
+}
+
+#[tokio::test]
+async fn children_guard_completes_children_from_hooks() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let parent = rand_message_id();
+    let (child, message) = message_with_parents(&[parent]);
+    tangle.insert(child, message, 0).await;
+    let other = rand_message_id();
+    tangle
+        .hooks()
+        .approvers
+        .lock()
+        .unwrap()
+        .get_mut(&parent)
+        .unwrap()
+        .push(other);
+
+    {
+        let children = tangle.children_guard(&parent).await.unwrap();
+        assert_eq!(&*children, &[child, other][..]);
+    }
+
+    // The guard was dropped, so the tangle can be written to again
+    let (last, message) = message_with_parents(&[parent]);
+    tangle.insert(last, message, 0).await;
+    assert_eq!(
+        &*tangle.children_guard(&parent).await.unwrap(),
+        &[child, other, last][..]
+    );
+}
+
+#[tokio::test]
+async fn get_approvers_count_leaves_uncached_vertices_alone() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let parent = rand_message_id();
+    tangle
+        .hooks()
+        .approvers
+        .lock()
+        .unwrap()
+        .insert(parent, rand_message_ids(2));
+
+    assert_eq!(tangle.get_approvers_count(&parent).await, 2);
+    assert!(tangle.is_empty().await);
+
+    // Children of cached vertices are completed on the way
+    let (child, message) = message_with_parents(&[parent]);
+    tangle.insert(child, message, 0).await;
+    assert_eq!(tangle.get_approvers_count(&parent).await, 3);
+    assert_eq!(
+        tangle.get_children_cached(&parent).await.map(|children| children.len()),
+        Some(3)
+    );
+}
+
+#[tokio::test]
+async fn warm_children_makes_children_exhaustive() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default()).with_shards(4);
+    let parents = rand_message_ids(8);
+    for parent in parents.iter() {
+        tangle
+            .hooks()
+            .approvers
+            .lock()
+            .unwrap()
+            .insert(*parent, rand_message_ids(2));
+    }
+
+    tangle.warm_children(stream::iter(parents.clone()), 3).await;
+
+    for parent in parents.iter() {
+        assert_eq!(
+            tangle.get_children_cached(parent).await.map(|children| children.len()),
+            Some(2)
+        );
+    }
+}
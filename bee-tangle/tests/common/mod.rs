@@ -0,0 +1,142 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+// Not every test file uses every helper.
+#![allow(dead_code)]
+
+use bee_message::{Message, MessageId, Parents};
+use bee_tangle::Hooks;
+use bee_test::rand::message::{rand_message_id, rand_message_with_parents};
+
+use async_trait::async_trait;
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+/// Creates a random message approving the given parents, which are sorted as required.
+pub fn message_with_parents(parents: &[MessageId]) -> (MessageId, Message) {
+    let mut parents = parents.to_vec();
+    parents.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+
+    (
+        rand_message_id(),
+        rand_message_with_parents(Parents::new(parents).unwrap()),
+    )
+}
+
+/// Creates a chain of `len` messages, each approving the previous one, starting from a random parent.
+pub fn message_chain(len: usize) -> Vec<(MessageId, Message)> {
+    let mut parent = rand_message_id();
+
+    (0..len)
+        .map(|_| {
+            let (message_id, message) = message_with_parents(&[parent]);
+            parent = message_id;
+            (message_id, message)
+        })
+        .collect()
+}
+
+/// In-memory hooks, to observe what the tangle writes through to them.
+#[derive(Default)]
+pub struct MemoryHooks {
+    pub messages: Mutex<HashMap<MessageId, (Message, u64)>>,
+    pub approvers: Mutex<HashMap<MessageId, Vec<MessageId>>>,
+    /// Makes every call fail while set.
+    pub fail: AtomicBool,
+    /// Delays every fetch of a message by the given duration.
+    pub get_delay: Option<Duration>,
+    /// Delays every insertion by the given duration.
+    pub insert_delay: Option<Duration>,
+}
+
+impl MemoryHooks {
+    pub fn with_get_delay(delay: Duration) -> Self {
+        Self {
+            get_delay: Some(delay),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_insert_delay(delay: Duration) -> Self {
+        Self {
+            insert_delay: Some(delay),
+            ..Self::default()
+        }
+    }
+
+    pub fn contains(&self, message_id: &MessageId) -> bool {
+        self.messages.lock().unwrap().contains_key(message_id)
+    }
+
+    pub fn metadata(&self, message_id: &MessageId) -> Option<u64> {
+        self.messages
+            .lock()
+            .unwrap()
+            .get(message_id)
+            .map(|(_, metadata)| *metadata)
+    }
+
+    fn check(&self) -> Result<(), ()> {
+        if self.fail.load(Ordering::SeqCst) {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[async_trait]
+impl Hooks<u64> for MemoryHooks {
+    type Error = ();
+
+    async fn get(&self, message_id: &MessageId) -> Result<Option<(Message, u64)>, Self::Error> {
+        if let Some(delay) = self.get_delay {
+            tokio::time::sleep(delay).await;
+        }
+        self.check()?;
+        Ok(self.messages.lock().unwrap().get(message_id).cloned())
+    }
+
+    async fn insert(&self, message_id: MessageId, message: Message, metadata: u64) -> Result<(), Self::Error> {
+        if let Some(delay) = self.insert_delay {
+            tokio::time::sleep(delay).await;
+        }
+        self.check()?;
+        self.messages.lock().unwrap().insert(message_id, (message, metadata));
+        Ok(())
+    }
+
+    async fn fetch_approvers(&self, message_id: &MessageId) -> Result<Option<Vec<MessageId>>, Self::Error> {
+        self.check()?;
+        Ok(self.approvers.lock().unwrap().get(message_id).cloned())
+    }
+
+    async fn insert_approver(&self, message_id: MessageId, approver: MessageId) -> Result<(), Self::Error> {
+        self.check()?;
+        self.approvers
+            .lock()
+            .unwrap()
+            .entry(message_id)
+            .or_default()
+            .push(approver);
+        Ok(())
+    }
+
+    async fn update_approvers(&self, message_id: MessageId, approvers: &[MessageId]) -> Result<(), Self::Error> {
+        self.check()?;
+        self.approvers.lock().unwrap().insert(message_id, approvers.to_vec());
+        Ok(())
+    }
+
+    async fn count(&self) -> Result<usize, Self::Error> {
+        self.check()?;
+        Ok(self.messages.lock().unwrap().len())
+    }
+}
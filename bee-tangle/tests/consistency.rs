@@ -0,0 +1,60 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "test-utils")]
+
+mod common;
+
+use common::{message_chain, message_with_parents};
+
+use bee_tangle::{InMemoryTangle, ShardedTangle, Tangle};
+use bee_test::rand::message::rand_message_ids;
+
+#[tokio::test]
+async fn insertions_leave_no_dangling_references() {
+    let tangle: ShardedTangle<u64> = Tangle::default().with_shards(4);
+    let messages = message_chain(20);
+
+    for (message_id, message) in messages.iter().cloned() {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    // Messages approving several messages of the chain at once
+    for window in messages.windows(3) {
+        let (message_id, message) = message_with_parents(&[window[0].0, window[2].0]);
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    tangle.assert_no_dangling_references().await;
+}
+
+#[tokio::test]
+#[should_panic]
+async fn eviction_leaves_dangling_references() {
+    let tangle = InMemoryTangle::<u64>::default();
+    let messages = message_chain(3);
+
+    for (message_id, message) in messages.iter().cloned() {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    let middle = messages[1].0;
+    tangle.evict_matching(|message_id, _| *message_id == middle).await;
+
+    tangle.assert_no_dangling_references().await;
+}
+
+#[tokio::test]
+async fn clear_empties_the_cache() {
+    let mut tangle = InMemoryTangle::<u64>::default();
+    let (message_id, message) = message_with_parents(&rand_message_ids(2));
+
+    tangle.insert(message_id, message, 0).await;
+    assert_eq!(tangle.len().await, 3);
+
+    tangle.clear().await;
+
+    assert!(tangle.is_empty().await);
+    assert!(!tangle.contains(&message_id).await);
+    tangle.assert_no_dangling_references().await;
+}
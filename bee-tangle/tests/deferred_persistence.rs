@@ -0,0 +1,62 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{message_with_parents, MemoryHooks};
+
+use bee_tangle::Tangle;
+use bee_test::rand::message::rand_message_ids;
+
+use std::sync::atomic::Ordering;
+
+#[tokio::test]
+async fn updates_deferred_until_flushed() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default()).with_deferred_persistence();
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+
+    tangle.insert(message_id, message, 0).await;
+    assert_eq!(tangle.hooks().metadata(&message_id), Some(0));
+
+    tangle.set_metadata(&message_id, 1).await;
+    assert_eq!(tangle.hooks().metadata(&message_id), Some(0));
+
+    assert_eq!(tangle.flush_dirty().await, Ok(1));
+    assert_eq!(tangle.hooks().metadata(&message_id), Some(1));
+    assert_eq!(tangle.flush_dirty().await, Ok(0));
+}
+
+#[tokio::test]
+async fn evicted_dirty_vertex_is_not_stale() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default()).with_deferred_persistence();
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+
+    tangle.insert(message_id, message, 0).await;
+    tangle.set_metadata(&message_id, 1).await;
+
+    assert_eq!(tangle.evict_matching(|id, _| *id == message_id).await, 1);
+    assert!(!tangle.contains_in_cache(&message_id).await);
+
+    // The hooks still hold the inserted metadata, but the dirty one takes precedence
+    assert_eq!(tangle.hooks().metadata(&message_id), Some(0));
+    assert_eq!(tangle.get_metadata(&message_id).await, Some(1));
+
+    assert_eq!(tangle.flush_dirty().await, Ok(1));
+    assert_eq!(tangle.hooks().metadata(&message_id), Some(1));
+}
+
+#[tokio::test]
+async fn failed_flush_keeps_vertices_dirty() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default()).with_deferred_persistence();
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+
+    tangle.insert(message_id, message, 0).await;
+    tangle.set_metadata(&message_id, 1).await;
+
+    tangle.hooks().fail.store(true, Ordering::SeqCst);
+    assert_eq!(tangle.flush_dirty().await, Err(()));
+
+    tangle.hooks().fail.store(false, Ordering::SeqCst);
+    assert_eq!(tangle.flush_dirty().await, Ok(1));
+    assert_eq!(tangle.hooks().metadata(&message_id), Some(1));
+}
@@ -0,0 +1,88 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{message_chain, message_with_parents, MemoryHooks};
+
+use bee_tangle::{CycleError, Tangle};
+use bee_test::rand::message::rand_message_id;
+
+use std::collections::HashSet;
+
+#[tokio::test]
+async fn get_all_children_recursive_up_to_depth() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let messages = message_chain(4);
+
+    for (message_id, message) in messages.iter().cloned() {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    let root = messages[0].0;
+    let all = messages[1..]
+        .iter()
+        .map(|(message_id, _)| *message_id)
+        .collect::<HashSet<_>>();
+
+    assert_eq!(tangle.get_all_children_recursive(&root, 10).await.unwrap(), all);
+    assert_eq!(
+        tangle.get_all_children_recursive(&root, 1).await.unwrap(),
+        std::iter::once(messages[1].0).collect::<HashSet<_>>()
+    );
+    assert!(tangle
+        .get_all_children_recursive(&messages[3].0, 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn get_all_children_recursive_detects_cycles() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (a, b) = (rand_message_id(), rand_message_id());
+
+    // The hooks can't be trusted to be acyclic
+    tangle.hooks().approvers.lock().unwrap().insert(a, vec![b]);
+    tangle.hooks().approvers.lock().unwrap().insert(b, vec![a]);
+
+    assert!(matches!(
+        tangle.get_all_children_recursive(&a, 10).await,
+        Err(CycleError(message_id)) if message_id == a
+    ));
+}
+
+#[tokio::test]
+async fn reachable_tips_from_a_message() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let messages = message_chain(2);
+    let (tip, message) = message_with_parents(&[messages[0].0]);
+
+    for (message_id, message) in messages.iter().cloned().chain(std::iter::once((tip, message))) {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    let mut tips = tangle.reachable_tips_from(&messages[0].0).await;
+    tips.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+    let mut expected = vec![messages[1].0, tip];
+    expected.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+
+    assert_eq!(tips, expected);
+    assert!(tangle.reachable_tips_from(&tip).await.is_empty());
+}
+
+#[tokio::test]
+async fn get_descendants_in_breadth_first_order() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let messages = message_chain(4);
+
+    for (message_id, message) in messages.iter().cloned() {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    let ids = messages.iter().map(|(message_id, _)| *message_id).collect::<Vec<_>>();
+
+    assert_eq!(tangle.get_descendants(&ids[0], 10).await, ids[1..].to_vec());
+    assert_eq!(tangle.get_descendants(&ids[0], 2).await, ids[1..3].to_vec());
+    assert!(tangle.get_descendants(&ids[0], 0).await.is_empty());
+}
@@ -0,0 +1,63 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_tangle::event::BoundedTangleNotifier;
+
+use futures::{pin_mut, FutureExt, StreamExt};
+
+#[tokio::test]
+async fn full_channels_drop_events() {
+    let notifier = BoundedTangleNotifier::new(2);
+    let slow = notifier.subscribe();
+    let fast = notifier.subscribe();
+    pin_mut!(slow);
+    pin_mut!(fast);
+
+    notifier.send(0);
+    assert_eq!(fast.next().await, Some(0));
+    notifier.send(1);
+    assert_eq!(fast.next().await, Some(1));
+
+    // Sending never waits for the slow subscriber, whose channel is now full
+    notifier.send(2);
+    assert_eq!(notifier.dropped_events(), 1);
+    assert_eq!(fast.next().await, Some(2));
+
+    assert_eq!(slow.next().await, Some(0));
+    assert_eq!(slow.next().await, Some(1));
+    assert!(slow.next().now_or_never().is_none());
+
+    // Room was made in the channel of the slow subscriber
+    notifier.send(3);
+    assert_eq!(notifier.dropped_events(), 1);
+    assert_eq!(slow.next().await, Some(3));
+    assert_eq!(fast.next().await, Some(3));
+}
+
+#[tokio::test]
+async fn dropped_subscribers_are_forgotten() {
+    let notifier = BoundedTangleNotifier::new(1);
+    let dropped = notifier.subscribe();
+    let kept = notifier.subscribe();
+    pin_mut!(kept);
+
+    drop(dropped);
+
+    // Events sent to a dropped subscriber are not counted as dropped, even once its channel would be full
+    notifier.send(0);
+    notifier.send(1);
+    assert_eq!(notifier.dropped_events(), 1);
+    assert_eq!(kept.next().await, Some(0));
+    assert!(kept.next().now_or_never().is_none());
+}
+
+#[tokio::test]
+async fn zero_capacity_is_clamped() {
+    let notifier = BoundedTangleNotifier::new(0);
+    let stream = notifier.subscribe();
+    pin_mut!(stream);
+
+    notifier.send(0);
+    assert_eq!(notifier.dropped_events(), 0);
+    assert_eq!(stream.next().await, Some(0));
+}
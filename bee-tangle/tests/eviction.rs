@@ -0,0 +1,138 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::message_chain;
+
+use bee_common::packable::Packable;
+use bee_tangle::InMemoryTangle;
+
+use std::sync::Arc;
+
+#[tokio::test]
+async fn eviction_keeps_cache_within_capacity() {
+    let tangle = InMemoryTangle::<u64>::default().with_capacity(100);
+
+    for (message_id, message) in message_chain(500) {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    assert!(tangle.len().await <= 100);
+    assert_eq!(tangle.stats().await.vertices, tangle.len().await);
+}
+
+#[tokio::test]
+async fn no_eviction_below_capacity() {
+    let tangle = InMemoryTangle::<u64>::default().with_capacity(100);
+    let messages = message_chain(10);
+
+    for (message_id, message) in messages.iter().cloned() {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    // The 10 messages and the parent of the first one
+    assert_eq!(tangle.len().await, 11);
+    for (message_id, _) in messages.iter() {
+        assert!(tangle.contains(message_id).await);
+    }
+}
+
+#[tokio::test]
+async fn resize_and_evict_shrinks_cache() {
+    let tangle = InMemoryTangle::<u64>::default().with_capacity(100);
+
+    for (message_id, message) in message_chain(50) {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    assert_eq!(tangle.len().await, 51);
+
+    let evicted = tangle.resize_and_evict(20).await;
+
+    assert_eq!(tangle.len().await, 51 - evicted);
+    assert!(tangle.len().await <= 20);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_insertions_stay_within_capacity() {
+    let tangle = Arc::new(InMemoryTangle::<u64>::default().with_capacity(100).with_shards(4));
+
+    let tasks = (0..8)
+        .map(|_| {
+            let tangle = tangle.clone();
+            tokio::spawn(async move {
+                for (message_id, message) in message_chain(100) {
+                    tangle.insert(message_id, message, 0).await;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    assert!(tangle.len().await <= 100);
+    assert_eq!(tangle.stats().await.vertices, tangle.len().await);
+}
+
+#[tokio::test]
+async fn evict_matching_vertices() {
+    let tangle = InMemoryTangle::<u64>::default().with_shards(4);
+    let messages = message_chain(6);
+
+    for (index, (message_id, message)) in messages.iter().cloned().enumerate() {
+        tangle.insert(message_id, message, index as u64).await;
+    }
+    let len = tangle.len().await;
+
+    let evicted = tangle
+        .evict_matching(|_, v| v.metadata().map_or(false, |m| m % 2 == 0))
+        .await;
+
+    assert_eq!(evicted, 3);
+    assert_eq!(tangle.len().await, len - 3);
+    for (index, (message_id, _)) in messages.iter().enumerate() {
+        assert_eq!(tangle.contains_in_cache(message_id).await, index % 2 == 1);
+    }
+    assert_eq!(tangle.evict_matching(|_, _| false).await, 0);
+}
+
+#[tokio::test]
+async fn drain_returns_the_messages() {
+    let tangle = InMemoryTangle::<u64>::default().with_shards(4);
+    let messages = message_chain(4);
+
+    for (message_id, message) in messages.iter().cloned() {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    let drained = tangle.drain().await;
+
+    assert_eq!(drained.len(), messages.len());
+    for (message_id, message) in messages.iter() {
+        let (_, drained) = drained.iter().find(|(id, _)| id == message_id).unwrap();
+        assert_eq!(drained.pack_new(), message.pack_new());
+    }
+    assert_eq!(tangle.len().await, 0);
+}
+
+#[tokio::test]
+async fn in_memory_tangle_forgets_evicted_messages() {
+    let tangle = InMemoryTangle::<u64>::default();
+    let messages = message_chain(2);
+
+    for (message_id, message) in messages.iter().cloned() {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    tangle.get(&messages[1].0).await;
+    tangle.resize_and_evict(1).await;
+
+    // There are no hooks to pull evicted messages back from
+    assert!(tangle.get(&messages[0].0).await.is_none());
+    assert!(!tangle.contains(&messages[0].0).await);
+    assert_eq!(tangle.get_children(&messages[0].0).await, Some(Vec::new()));
+    assert_eq!(tangle.num_children(&messages[1].0).await, 0);
+}
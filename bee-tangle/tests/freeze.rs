@@ -0,0 +1,78 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{message_chain, message_with_parents, MemoryHooks};
+
+use bee_tangle::{InMemoryTangle, Tangle};
+use bee_test::rand::message::rand_message_ids;
+
+use tokio::time::{sleep, timeout};
+
+use std::{sync::Arc, time::Duration};
+
+#[tokio::test]
+async fn freeze_and_unfreeze() {
+    let tangle = InMemoryTangle::<u64>::default();
+
+    assert!(!tangle.is_frozen());
+    tangle.freeze().await;
+    assert!(tangle.is_frozen());
+    tangle.unfreeze().await;
+    assert!(!tangle.is_frozen());
+
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    assert!(tangle.insert(message_id, message, 0).await.is_some());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn insertions_queue_while_frozen() {
+    let tangle = Arc::new(InMemoryTangle::<u64>::default());
+
+    tangle.freeze().await;
+
+    let tasks = message_chain(8)
+        .into_iter()
+        .map(|(message_id, message)| {
+            let tangle = tangle.clone();
+            tokio::spawn(async move { tangle.insert(message_id, message, 0).await })
+        })
+        .collect::<Vec<_>>();
+
+    sleep(Duration::from_millis(50)).await;
+    assert!(tangle.is_empty().await);
+
+    tangle.unfreeze().await;
+
+    for task in tasks {
+        let inserted = timeout(Duration::from_secs(1), task).await.unwrap().unwrap();
+        assert!(inserted.is_some());
+    }
+    assert_eq!(tangle.len().await, 9);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn freeze_waits_for_insertions_in_flight() {
+    let tangle = Arc::new(Tangle::<u64, _>::new(MemoryHooks::with_insert_delay(
+        Duration::from_millis(200),
+    )));
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+
+    let task = tokio::spawn({
+        let tangle = tangle.clone();
+        async move { tangle.insert(message_id, message, 0).await }
+    });
+
+    // Let the insertion reach the hooks
+    sleep(Duration::from_millis(50)).await;
+    assert!(!tangle.hooks().contains(&message_id));
+
+    tangle.freeze().await;
+
+    // The insertion completed, including its write to the hooks, before the tangle got frozen
+    assert!(tangle.hooks().contains(&message_id));
+    assert!(task.await.unwrap().is_some());
+
+    tangle.unfreeze().await;
+}
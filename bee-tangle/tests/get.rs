@@ -0,0 +1,197 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{message_with_parents, MemoryHooks};
+
+use bee_common::packable::Packable;
+use bee_tangle::{InMemoryTangle, Source, Tangle};
+use bee_test::rand::message::{rand_message_id, rand_message_ids};
+
+use std::sync::atomic::Ordering;
+
+#[tokio::test]
+async fn get_with_metadata() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (message_id, message) = message_with_parents(&rand_message_ids(2));
+
+    tangle.insert(message_id, message.clone(), 5).await;
+
+    let (message_ref, metadata) = tangle.get_with_metadata(&message_id).await.unwrap();
+    assert_eq!(message_ref.pack_new(), message.pack_new());
+    assert_eq!(metadata, 5);
+
+    // Messages are pulled from the hooks along with their metadata
+    let (stored_id, stored) = message_with_parents(&rand_message_ids(1));
+    tangle
+        .hooks()
+        .messages
+        .lock()
+        .unwrap()
+        .insert(stored_id, (stored.clone(), 9));
+    let (message_ref, metadata) = tangle.get_with_metadata(&stored_id).await.unwrap();
+    assert_eq!(message_ref.pack_new(), stored.pack_new());
+    assert_eq!(metadata, 9);
+
+    assert!(InMemoryTangle::<u64>::default()
+        .get_with_metadata(&rand_message_id())
+        .await
+        .is_none());
+}
+
+#[tokio::test]
+async fn get_if_filters_on_metadata() {
+    let tangle = InMemoryTangle::<u64>::default();
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(message_id, message.clone(), 3).await;
+
+    let message_ref = tangle.get_if(&message_id, |m| *m == 3).await.unwrap();
+    assert_eq!(message_ref.pack_new(), message.pack_new());
+    assert!(tangle.get_if(&message_id, |m| *m > 3).await.is_none());
+    assert!(tangle.get_if(&rand_message_id(), |_| true).await.is_none());
+}
+
+#[tokio::test]
+async fn contains_all_and_any_from_cache_and_hooks() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (cached, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(cached, message, 0).await;
+    let (stored, message) = message_with_parents(&rand_message_ids(1));
+    tangle.hooks().messages.lock().unwrap().insert(stored, (message, 0));
+    let unknown = rand_message_id();
+
+    assert!(tangle.contains_all(&[cached, stored]).await);
+    assert!(!tangle.contains_all(&[cached, stored, unknown]).await);
+    assert!(tangle.contains_all(&[]).await);
+
+    assert!(tangle.contains_any(&[unknown, stored]).await);
+    assert!(!tangle.contains_any(&[unknown]).await);
+    assert!(!tangle.contains_any(&[]).await);
+}
+
+#[tokio::test]
+async fn get_with_metadata_or_default_only_needs_the_message() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let parent = rand_message_id();
+    let (message_id, message) = message_with_parents(&[parent]);
+    tangle.insert(message_id, message.clone(), 6).await;
+
+    let (message_ref, metadata) = tangle.get_with_metadata_or_default(&message_id).await.unwrap();
+    assert_eq!(message_ref.pack_new(), message.pack_new());
+    assert_eq!(metadata, Some(6));
+
+    // The parent only has a vertex for its children, without a message
+    assert!(tangle.get_with_metadata_or_default(&parent).await.is_none());
+    assert!(tangle.get_with_metadata_or_default(&rand_message_id()).await.is_none());
+}
+
+#[tokio::test]
+async fn get_vertex_snapshot_holds_no_lock() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(message_id, message.clone(), 1).await;
+
+    let snapshot = tangle.get_vertex_snapshot(&message_id).await.unwrap();
+
+    // The tangle can be written to while the snapshot is held
+    let (child, child_message) = message_with_parents(&[message_id]);
+    tangle.insert(child, child_message, 0).await;
+    tangle.set_metadata(&message_id, 2).await;
+
+    assert_eq!(snapshot.message.unwrap().pack_new(), message.pack_new());
+    assert_eq!(snapshot.metadata, Some(1));
+    assert!(snapshot.children.is_empty());
+    assert!(!snapshot.children_exhaustive);
+
+    let snapshot = tangle.get_vertex_snapshot(&message_id).await.unwrap();
+    assert_eq!(snapshot.metadata, Some(2));
+    assert_eq!(snapshot.children, vec![child]);
+
+    assert!(tangle.get_vertex_snapshot(&rand_message_id()).await.is_none());
+}
+
+#[tokio::test]
+async fn contains_in_cache_ignores_the_hooks() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (cached, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(cached, message, 0).await;
+    let (stored, message) = message_with_parents(&rand_message_ids(1));
+    tangle.hooks().messages.lock().unwrap().insert(stored, (message, 0));
+
+    assert!(tangle.contains_in_cache(&cached).await);
+    assert!(!tangle.contains_in_cache(&stored).await);
+    assert!(!tangle.contains_in_cache(&message_with_parents(&[cached]).0).await);
+
+    // Looking the message up brings it into the cache
+    assert!(tangle.contains(&stored).await);
+    assert!(tangle.contains_in_cache(&stored).await);
+}
+
+#[tokio::test]
+async fn get_with_source_reports_where_messages_come_from() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (cached, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(cached, message, 0).await;
+    let (stored, message) = message_with_parents(&rand_message_ids(1));
+    tangle
+        .hooks()
+        .messages
+        .lock()
+        .unwrap()
+        .insert(stored, (message.clone(), 0));
+
+    let (message_ref, source) = tangle.get_with_source(&stored).await;
+    assert_eq!(message_ref.unwrap().pack_new(), message.pack_new());
+    assert_eq!(source, Source::Backend);
+
+    // The message was brought into the cache along the way
+    assert_eq!(tangle.get_with_source(&stored).await.1, Source::Cache);
+    assert_eq!(tangle.get_with_source(&cached).await.1, Source::Cache);
+
+    let (message_ref, source) = tangle.get_with_source(&rand_message_id()).await;
+    assert!(message_ref.is_none());
+    assert_eq!(source, Source::Missing);
+}
+
+#[tokio::test]
+async fn get_in_order_from_cache_and_hooks() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default()).with_shards(4);
+    let (cached, cached_message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(cached, cached_message.clone(), 0).await;
+    let (stored, stored_message) = message_with_parents(&rand_message_ids(1));
+    tangle
+        .hooks()
+        .messages
+        .lock()
+        .unwrap()
+        .insert(stored, (stored_message.clone(), 0));
+
+    let messages = tangle.get_in_order(&[stored, rand_message_id(), cached]).await;
+
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[0].as_ref().unwrap().pack_new(), stored_message.pack_new());
+    assert!(messages[1].is_none());
+    assert_eq!(messages[2].as_ref().unwrap().pack_new(), cached_message.pack_new());
+}
+
+#[tokio::test]
+async fn exists_in_backend_bypasses_the_cache() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(message_id, message.clone(), 0).await;
+
+    assert_eq!(tangle.exists_in_backend(&message_id).await, Ok(true));
+
+    // The cache is not consulted, and the message is not brought into it
+    tangle.hooks().messages.lock().unwrap().clear();
+    assert_eq!(tangle.exists_in_backend(&message_id).await, Ok(false));
+
+    let (stored, message) = message_with_parents(&rand_message_ids(1));
+    tangle.hooks().messages.lock().unwrap().insert(stored, (message, 0));
+    assert_eq!(tangle.exists_in_backend(&stored).await, Ok(true));
+    assert!(!tangle.contains_in_cache(&stored).await);
+
+    tangle.hooks().fail.store(true, Ordering::SeqCst);
+    assert_eq!(tangle.exists_in_backend(&stored).await, Err(()));
+}
@@ -0,0 +1,44 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::message_chain;
+
+use bee_tangle::InMemoryTangle;
+
+#[tokio::test]
+async fn tips_and_solid_vertices_are_styled() {
+    let tangle = InMemoryTangle::<u64>::default();
+    let messages = message_chain(3);
+
+    for (index, (message_id, message)) in messages.iter().cloned().enumerate() {
+        tangle.insert(message_id, message, index as u64).await;
+    }
+
+    let (solid, _) = messages[1];
+    let (tip, _) = messages[0];
+    let (childless, _) = messages[2];
+
+    let mut graph = Vec::new();
+    tangle
+        .write_graphviz(&mut graph, |metadata| *metadata == 1, |message_id| *message_id == tip)
+        .await
+        .unwrap();
+    let graph = String::from_utf8(graph).unwrap();
+
+    let node = |message_id: &bee_message::MessageId| {
+        graph
+            .lines()
+            .find(|line| line.contains(&format!("\"{}\" [", message_id)))
+            .unwrap()
+            .to_owned()
+    };
+
+    assert!(graph.starts_with("digraph {"));
+    // Only vertices in the tip pool are outlined, whether they have children or not
+    assert!(node(&tip).contains("style=\"bold\""));
+    assert!(node(&childless).contains("style=\"\""));
+    assert!(node(&solid).contains("style=\"filled\""));
+    assert!(graph.contains(&format!("\"{}\" -> \"{}\";", tip, solid)));
+}
@@ -0,0 +1,109 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{message_chain, message_with_parents, MemoryHooks};
+
+use bee_tangle::{InMemoryTangle, InsertResult, Tangle};
+use bee_test::rand::message::rand_message_ids;
+
+use std::sync::Arc;
+
+#[tokio::test]
+async fn insertion_refused_when_full() {
+    // The capacity is above the cap so that eviction never makes room
+    let tangle = InMemoryTangle::<u64>::default().with_capacity(100).with_hard_cap(11);
+
+    for (message_id, message) in message_chain(9) {
+        assert!(matches!(
+            tangle.insert_if_absent(message_id, message, 0).await,
+            InsertResult::Inserted(_)
+        ));
+    }
+
+    assert_eq!(tangle.len().await, 10);
+
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    assert!(matches!(
+        tangle.insert_if_absent(message_id, message, 0).await,
+        InsertResult::CacheFull
+    ));
+    assert!(!tangle.contains(&message_id).await);
+    assert_eq!(tangle.len().await, 10);
+}
+
+#[tokio::test]
+async fn full_cache_does_not_pull_from_hooks() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default())
+        .with_capacity(100)
+        .with_hard_cap(11);
+
+    for (message_id, message) in message_chain(9) {
+        tangle.insert_if_absent(message_id, message, 0).await;
+    }
+
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    tangle
+        .hooks()
+        .messages
+        .lock()
+        .unwrap()
+        .insert(message_id, (message.clone(), 0));
+
+    assert!(matches!(
+        tangle.insert_if_absent(message_id, message, 0).await,
+        InsertResult::CacheFull
+    ));
+    assert!(!tangle.contains_in_cache(&message_id).await);
+    assert_eq!(tangle.len().await, 10);
+}
+
+#[tokio::test]
+async fn eviction_makes_room_below_cap() {
+    let tangle = InMemoryTangle::<u64>::default().with_capacity(10).with_hard_cap(12);
+
+    for (message_id, message) in message_chain(50) {
+        assert!(matches!(
+            tangle.insert_if_absent(message_id, message, 0).await,
+            InsertResult::Inserted(_)
+        ));
+        assert!(tangle.len().await <= 12);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_insertions_never_exceed_cap() {
+    let tangle = Arc::new(
+        InMemoryTangle::<u64>::default()
+            .with_capacity(1000)
+            .with_hard_cap(50)
+            .with_shards(4),
+    );
+
+    let tasks = (0..16)
+        .map(|_| {
+            let tangle = tangle.clone();
+            tokio::spawn(async move {
+                let mut full = 0;
+                for _ in 0..10 {
+                    let (message_id, message) = message_with_parents(&rand_message_ids(2));
+                    if let InsertResult::CacheFull = tangle.insert_if_absent(message_id, message, 0).await {
+                        full += 1;
+                    }
+                }
+                full
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut full = 0;
+    for task in tasks {
+        full += task.await.unwrap();
+    }
+
+    // Each message brings 3 vertices along, so only 16 of the 160 messages fit
+    assert_eq!(full, 160 - 16);
+    assert_eq!(tangle.len().await, 48);
+    assert_eq!(tangle.stats().await.messages, 16);
+}
@@ -0,0 +1,185 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{message_chain, message_with_parents, MemoryHooks};
+
+use bee_common::packable::Packable;
+use bee_tangle::{InsertResult, Tangle};
+use bee_test::rand::message::rand_message_ids;
+
+use std::sync::{Arc, Mutex};
+
+#[tokio::test]
+async fn insert_if_absent_reports_what_happened() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+
+    assert!(matches!(
+        tangle.insert_if_absent(message_id, message.clone(), 1).await,
+        InsertResult::Inserted(_)
+    ));
+
+    // The present message is returned, and left untouched
+    match tangle.insert_if_absent(message_id, message.clone(), 2).await {
+        InsertResult::AlreadyPresent(message_ref) => assert_eq!(message_ref.pack_new(), message.pack_new()),
+        _ => panic!("expected the message to be already present"),
+    }
+    assert_eq!(tangle.get_metadata(&message_id).await, Some(1));
+    assert_eq!(tangle.hooks().metadata(&message_id), Some(1));
+
+    // Messages only known by the hooks are present too
+    let (stored_id, stored) = message_with_parents(&rand_message_ids(1));
+    tangle
+        .hooks()
+        .messages
+        .lock()
+        .unwrap()
+        .insert(stored_id, (stored.clone(), 7));
+    assert!(matches!(
+        tangle.insert_if_absent(stored_id, stored, 8).await,
+        InsertResult::AlreadyPresent(_)
+    ));
+    assert_eq!(tangle.hooks().metadata(&stored_id), Some(7));
+}
+
+#[tokio::test]
+async fn messages_from_other_networks_are_rejected() {
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    let network_id = message.network_id();
+
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default()).with_network_filter(network_id.wrapping_add(1));
+    assert!(matches!(
+        tangle.insert_if_absent(message_id, message.clone(), 0).await,
+        InsertResult::Rejected
+    ));
+    assert!(!tangle.contains(&message_id).await);
+    assert!(!tangle.hooks().contains(&message_id));
+
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default()).with_network_filter(network_id);
+    assert!(matches!(
+        tangle.insert_if_absent(message_id, message, 0).await,
+        InsertResult::Inserted(_)
+    ));
+}
+
+#[tokio::test]
+async fn oversized_messages_are_rejected() {
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    let size = message.packed_len();
+
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default()).with_max_message_size(size - 1);
+    assert!(matches!(
+        tangle.insert_if_absent(message_id, message.clone(), 0).await,
+        InsertResult::Rejected
+    ));
+    assert!(!tangle.contains(&message_id).await);
+    assert!(!tangle.hooks().contains(&message_id));
+
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default()).with_max_message_size(size);
+    assert!(matches!(
+        tangle.insert_if_absent(message_id, message, 0).await,
+        InsertResult::Inserted(_)
+    ));
+}
+
+#[tokio::test]
+async fn insert_with_hash_uses_the_message_id() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (_, message) = message_with_parents(&rand_message_ids(1));
+    let message_id = message.hash();
+
+    assert!(tangle.insert_with_hash(message.clone(), 0).await.is_some());
+    assert!(tangle.contains(&message_id).await);
+    assert!(tangle.hooks().contains(&message_id));
+
+    assert!(tangle.insert_with_hash(message, 0).await.is_none());
+}
+
+#[tokio::test]
+async fn on_insert_sees_new_vertices_only() {
+    let mut tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let inserted = Arc::new(Mutex::new(Vec::new()));
+
+    tangle.set_on_insert({
+        let inserted = inserted.clone();
+        move |message_id, vertex| {
+            inserted.lock().unwrap().push(*message_id);
+            *vertex.metadata_mut().unwrap() += 10;
+        }
+    });
+
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(message_id, message.clone(), 1).await;
+    tangle.insert(message_id, message, 2).await;
+
+    assert_eq!(*inserted.lock().unwrap(), vec![message_id]);
+    assert_eq!(tangle.get_metadata(&message_id).await, Some(11));
+}
+
+#[tokio::test]
+async fn duplicates_in_the_cache_skip_the_hooks() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(message_id, message.clone(), 0).await;
+
+    // The hooks would get the message back if they were written to again
+    tangle.hooks().messages.lock().unwrap().clear();
+    assert!(matches!(
+        tangle.insert_if_absent(message_id, message, 0).await,
+        InsertResult::AlreadyPresent(_)
+    ));
+
+    assert!(!tangle.hooks().contains(&message_id));
+    assert_eq!(tangle.len().await, 2);
+}
+
+#[tokio::test]
+async fn parallel_insert_keeps_the_order_of_results() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default()).with_shards(4);
+    let mut messages = message_chain(16)
+        .into_iter()
+        .map(|(message_id, message)| (message_id, message, 0))
+        .collect::<Vec<_>>();
+    // A duplicate is only inserted once
+    messages.push(messages[3].clone());
+
+    let results = tangle.parallel_insert(messages.clone()).await;
+
+    assert_eq!(results.len(), 17);
+    assert_eq!(results.iter().filter(|result| result.is_some()).count(), 16);
+    for (result, (_, message, _)) in results.iter().zip(messages.iter()) {
+        if let Some(message_ref) = result {
+            assert_eq!(message_ref.pack_new(), message.pack_new());
+        }
+    }
+    for (message_id, _, _) in messages.iter() {
+        assert!(tangle.hooks().contains(message_id));
+    }
+}
+
+#[tokio::test]
+async fn insert_if_absent_in_cache_skips_the_hook_lookup() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+
+    // Unlike insert_if_absent, the message only known by the hooks is inserted and written again
+    tangle
+        .hooks()
+        .messages
+        .lock()
+        .unwrap()
+        .insert(message_id, (message.clone(), 1));
+    assert!(matches!(
+        tangle.insert_if_absent_in_cache(message_id, message.clone(), 2).await,
+        InsertResult::Inserted(_)
+    ));
+    assert_eq!(tangle.hooks().metadata(&message_id), Some(2));
+
+    assert!(matches!(
+        tangle.insert_if_absent_in_cache(message_id, message, 3).await,
+        InsertResult::AlreadyPresent(_)
+    ));
+    assert_eq!(tangle.get_metadata(&message_id).await, Some(2));
+}
@@ -0,0 +1,126 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{message_with_parents, MemoryHooks};
+
+use bee_tangle::Tangle;
+use bee_test::rand::message::{rand_message_id, rand_message_ids};
+
+#[tokio::test]
+async fn compare_and_set_metadata() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(message_id, message, 1).await;
+
+    let (metadata, version) = tangle.get_metadata_versioned(&message_id).await.unwrap();
+    assert_eq!(metadata, 1);
+
+    assert!(tangle.compare_and_set_metadata(&message_id, version, 2).await);
+    // The version moved on, so a second update based on the same version is refused
+    assert!(!tangle.compare_and_set_metadata(&message_id, version, 3).await);
+
+    let (metadata, new_version) = tangle.get_metadata_versioned(&message_id).await.unwrap();
+    assert_eq!(metadata, 2);
+    assert!(new_version != version);
+    assert_eq!(tangle.hooks().metadata(&message_id), Some(2));
+
+    // Any other update bumps the version too
+    tangle.set_metadata(&message_id, 4).await;
+    assert!(!tangle.compare_and_set_metadata(&message_id, new_version, 5).await);
+
+    assert!(tangle.get_metadata_versioned(&rand_message_id()).await.is_none());
+    assert!(!tangle.compare_and_set_metadata(&rand_message_id(), 0, 0).await);
+}
+
+#[tokio::test]
+async fn unchanged_metadata_keeps_its_version() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(message_id, message, 1).await;
+
+    let (_, version) = tangle.get_metadata_versioned(&message_id).await.unwrap();
+
+    tangle.set_metadata(&message_id, 1).await;
+    tangle.update_metadata(&message_id, |m| *m).await;
+
+    assert_eq!(tangle.get_metadata_versioned(&message_id).await, Some((1, version)));
+    assert!(tangle.compare_and_set_metadata(&message_id, version, 2).await);
+}
+
+#[tokio::test]
+async fn unchanged_metadata_is_not_persisted() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(message_id, message, 1).await;
+    tangle.hooks().messages.lock().unwrap().clear();
+
+    tangle.set_metadata(&message_id, 1).await;
+    assert!(!tangle.hooks().contains(&message_id));
+
+    tangle.set_metadata(&message_id, 2).await;
+    assert_eq!(tangle.hooks().metadata(&message_id), Some(2));
+}
+
+#[tokio::test]
+async fn insert_or_merge_combines_metadata() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+
+    assert!(tangle
+        .insert_or_merge(message_id, message.clone(), 1, |m, new| *m += new)
+        .await
+        .is_some());
+    assert!(tangle
+        .insert_or_merge(message_id, message, 2, |m, new| *m += new)
+        .await
+        .is_none());
+
+    assert_eq!(tangle.get_metadata(&message_id).await, Some(3));
+    assert_eq!(tangle.hooks().metadata(&message_id), Some(3));
+}
+
+#[tokio::test]
+async fn insert_or_merge_skips_rejected_messages() {
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    let network_id = message.network_id();
+    let hooks = MemoryHooks::default();
+    hooks.messages.lock().unwrap().insert(message_id, (message.clone(), 1));
+    let tangle = Tangle::<u64, _>::new(hooks).with_network_filter(network_id.wrapping_add(1));
+
+    assert!(tangle
+        .insert_or_merge(message_id, message, 2, |m, new| *m += new)
+        .await
+        .is_none());
+
+    assert_eq!(tangle.hooks().metadata(&message_id), Some(1));
+}
+
+#[tokio::test]
+async fn get_metadata_or_tells_unknown_messages_apart() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let parent = rand_message_id();
+    let (message_id, message) = message_with_parents(&[parent]);
+    tangle.insert(message_id, message, 2).await;
+
+    assert_eq!(tangle.get_metadata_or(&message_id, 0).await, Some(2));
+
+    // The parent only has a vertex for its children, without a message
+    assert_eq!(tangle.get_metadata_or(&parent, 0).await, None);
+    assert_eq!(tangle.get_metadata_or(&rand_message_id(), 0).await, None);
+}
+
+#[tokio::test]
+async fn replace_metadata_returns_the_previous_one() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(message_id, message, 1).await;
+
+    assert_eq!(tangle.replace_metadata(&message_id, 2).await, Some(1));
+    assert_eq!(tangle.replace_metadata(&message_id, 3).await, Some(2));
+    assert_eq!(tangle.get_metadata(&message_id).await, Some(3));
+    assert_eq!(tangle.hooks().metadata(&message_id), Some(3));
+
+    assert_eq!(tangle.replace_metadata(&rand_message_id(), 4).await, None);
+}
@@ -0,0 +1,61 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "metrics")]
+
+mod common;
+
+use common::message_chain;
+
+use bee_tangle::{NullHooks, Tangle};
+
+use prometheus::Registry;
+
+#[tokio::test]
+async fn evictions_and_cache_size() {
+    let registry = Registry::new();
+    let tangle = Tangle::<u64>::new_with_metrics(NullHooks::default(), &registry)
+        .unwrap()
+        .with_capacity(100);
+    let metrics = tangle.metrics().unwrap().clone();
+    let messages = message_chain(50);
+
+    for (message_id, message) in messages.iter().cloned() {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    assert_eq!(metrics.inserts.get() as u64, 50);
+    assert_eq!(metrics.cache_size.get() as usize, tangle.len().await);
+    assert_eq!(metrics.evictions.get() as u64, 0);
+
+    let evicted = tangle.resize_and_evict(20).await;
+
+    assert_eq!(metrics.evictions.get() as usize, evicted);
+    assert_eq!(metrics.cache_size.get() as usize, tangle.len().await);
+
+    let (message_id, _) = messages.last().unwrap();
+    assert!(tangle.contains_in_cache(message_id).await);
+    assert_eq!(tangle.evict_matching(|id, _| id == message_id).await, 1);
+
+    assert_eq!(metrics.evictions.get() as usize, evicted + 1);
+    assert_eq!(metrics.cache_size.get() as usize, tangle.len().await);
+}
+
+#[tokio::test]
+async fn vertex_lock_waits_and_readers() {
+    let registry = Registry::new();
+    let tangle = Tangle::<u64>::new_with_metrics(NullHooks::default(), &registry).unwrap();
+    let metrics = tangle.metrics().unwrap().clone();
+    let (message_id, message) = message_chain(1).pop().unwrap();
+
+    tangle.insert(message_id, message, 0).await;
+    assert!(metrics.write_wait_time.get_sample_count() > 0);
+
+    let reads = metrics.read_wait_time.get_sample_count();
+    {
+        let _vertex = tangle.get_vertex(&message_id).await.unwrap();
+        assert_eq!(metrics.concurrent_readers.get(), 1);
+    }
+    assert_eq!(metrics.concurrent_readers.get(), 0);
+    assert!(metrics.read_wait_time.get_sample_count() > reads);
+}
@@ -0,0 +1,60 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::message_with_parents;
+
+use bee_tangle::{event::StreamEvent, InMemoryTangle};
+use bee_test::rand::message::rand_message_ids;
+
+use futures::{pin_mut, StreamExt};
+
+#[tokio::test]
+async fn metadata_changes_are_notified() {
+    let tangle = InMemoryTangle::<u64>::default().with_metadata_notifications(8);
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(message_id, message, 0).await;
+
+    let changes = tangle.metadata_changed();
+    pin_mut!(changes);
+
+    tangle.set_metadata(&message_id, 1).await;
+    // Leaves the metadata unchanged, so isn't notified
+    tangle.set_metadata(&message_id, 1).await;
+    tangle.update_metadata(&message_id, |m| *m += 1).await;
+
+    assert!(matches!(changes.next().await, Some(StreamEvent::Event((id, 1))) if id == message_id));
+    assert!(matches!(changes.next().await, Some(StreamEvent::Event((id, 2))) if id == message_id));
+}
+
+#[tokio::test]
+async fn no_notifications_by_default() {
+    let tangle = InMemoryTangle::<u64>::default();
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(message_id, message, 0).await;
+
+    let changes = tangle.metadata_changed();
+    pin_mut!(changes);
+
+    tangle.set_metadata(&message_id, 1).await;
+    assert!(changes.next().await.is_none());
+}
+
+#[tokio::test]
+async fn lagging_observers_are_told_how_many_changes_they_missed() {
+    let tangle = InMemoryTangle::<u64>::default().with_metadata_notifications(2);
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+    tangle.insert(message_id, message, 0).await;
+
+    let changes = tangle.metadata_changed();
+    pin_mut!(changes);
+
+    for metadata in 1..=4 {
+        tangle.set_metadata(&message_id, metadata).await;
+    }
+
+    assert!(matches!(changes.next().await, Some(StreamEvent::Lagged(2))));
+    assert!(matches!(changes.next().await, Some(StreamEvent::Event((_, 3)))));
+    assert!(matches!(changes.next().await, Some(StreamEvent::Event((_, 4)))));
+}
@@ -0,0 +1,117 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{message_chain, message_with_parents, MemoryHooks};
+
+use bee_message::MessageId;
+use bee_tangle::Tangle;
+use bee_test::rand::message::rand_message_id;
+
+#[tokio::test]
+async fn past_cone_flag_is_lost_on_eviction() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let messages = message_chain(2);
+
+    for (message_id, message) in messages.iter().cloned() {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    let (message_id, _) = messages[0];
+    assert!(!tangle.has_complete_past_cone(&message_id).await);
+    tangle.set_past_cone_complete(&message_id).await;
+    assert!(tangle.has_complete_past_cone(&message_id).await);
+    assert!(
+        tangle
+            .get_vertex_snapshot(&message_id)
+            .await
+            .unwrap()
+            .past_cone_complete
+    );
+
+    // Flagging a message outside of the cache does nothing
+    let unknown = rand_message_id();
+    tangle.set_past_cone_complete(&unknown).await;
+    assert!(!tangle.has_complete_past_cone(&unknown).await);
+
+    tangle.get(&messages[1].0).await;
+    tangle.resize_and_evict(1).await;
+
+    // The message is pulled back from the hooks without the flag
+    assert!(tangle.get(&message_id).await.is_some());
+    assert!(!tangle.has_complete_past_cone(&message_id).await);
+}
+
+#[tokio::test]
+async fn topological_order_puts_parents_first() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let messages = message_chain(3);
+    let (root, message) = message_with_parents(&[messages[0].0, messages[2].0]);
+
+    // Insert children before their parents, and leave one message in the hooks only
+    tangle.insert(root, message, 0).await;
+    for (message_id, message) in messages.iter().skip(1).rev().cloned() {
+        tangle.insert(message_id, message, 0).await;
+    }
+    let (message_id, message) = messages[0].clone();
+    tangle
+        .hooks()
+        .messages
+        .lock()
+        .unwrap()
+        .insert(message_id, (message.clone(), 0));
+
+    let (order, missing) = tangle.topological_order(&[root]).await;
+
+    let position = |message_id: MessageId| order.iter().position(|id| *id == message_id).unwrap();
+    assert_eq!(order.len(), 4);
+    assert!(position(messages[0].0) < position(messages[1].0));
+    assert!(position(messages[1].0) < position(messages[2].0));
+    assert!(position(messages[2].0) < position(root));
+
+    // The cone is not solid, as the first message approves an unknown parent
+    assert_eq!(missing.len(), 1);
+    assert!(missing.contains(&message.parents()[0]));
+}
+
+#[tokio::test]
+async fn find_common_ancestor_within_depth() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let messages = message_chain(3);
+    let (a, message_a) = message_with_parents(&[messages[2].0]);
+    let (b, message_b) = message_with_parents(&[messages[0].0]);
+
+    for (message_id, message) in messages.iter().cloned().chain(vec![(a, message_a), (b, message_b)]) {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    assert_eq!(tangle.find_common_ancestor(&a, &b, 4).await, Some(messages[0].0));
+    assert_eq!(tangle.find_common_ancestor(&a, &b, 1).await, None);
+    assert_eq!(
+        tangle.find_common_ancestor(&a, &messages[1].0, 4).await,
+        Some(messages[1].0)
+    );
+    assert_eq!(tangle.find_common_ancestor(&a, &a, 0).await, Some(a));
+}
+
+#[tokio::test]
+async fn past_cone_boundary_stops_at_matching_metadata() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default());
+    let messages = message_chain(4);
+
+    // The second message is the boundary, e.g. a confirmed one
+    for (index, (message_id, message)) in messages.iter().cloned().enumerate() {
+        tangle.insert(message_id, message, index as u64).await;
+    }
+
+    assert_eq!(
+        tangle.past_cone_boundary(&messages[3].0, |m| *m <= 1).await,
+        vec![messages[1].0]
+    );
+    assert!(tangle.past_cone_boundary(&messages[3].0, |_| false).await.is_empty());
+    assert_eq!(
+        tangle.past_cone_boundary(&messages[3].0, |_| true).await,
+        vec![messages[3].0]
+    );
+}
@@ -0,0 +1,46 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "tower")]
+
+mod common;
+
+use common::message_with_parents;
+
+use bee_tangle::{
+    service::{TangleRequest, TangleResponse, TangleService},
+    InMemoryTangle,
+};
+use bee_test::rand::message::rand_message_ids;
+
+use tower::ServiceExt;
+
+use std::sync::Arc;
+
+#[tokio::test]
+async fn oneshot_requests() {
+    let service = TangleService::new(Arc::new(InMemoryTangle::<u64>::default()));
+    let parents = rand_message_ids(1);
+    let (message_id, message) = message_with_parents(&parents);
+
+    // The futures own the tangle, so they can be spawned
+    let inserted = tokio::spawn(service.clone().oneshot(TangleRequest::Insert(message_id, message, 3)));
+    assert!(matches!(inserted.await.unwrap(), Ok(TangleResponse::Inserted(Some(_)))));
+
+    assert!(matches!(
+        service.clone().oneshot(TangleRequest::Contains(message_id)).await,
+        Ok(TangleResponse::Contains(true))
+    ));
+    assert!(matches!(
+        service.clone().oneshot(TangleRequest::GetMetadata(message_id)).await,
+        Ok(TangleResponse::Metadata(Some(3)))
+    ));
+    assert!(matches!(
+        service.clone().oneshot(TangleRequest::GetChildren(parents[0])).await,
+        Ok(TangleResponse::Children(Some(children))) if children == vec![message_id]
+    ));
+    assert!(matches!(
+        service.oneshot(TangleRequest::Get(message_id)).await,
+        Ok(TangleResponse::Message(Some(_)))
+    ));
+}
@@ -0,0 +1,96 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{message_chain, message_with_parents};
+
+use bee_tangle::{ShardedTangle, Tangle};
+use bee_test::rand::message::rand_message_ids;
+
+use std::sync::Arc;
+
+#[tokio::test]
+async fn sharded_insert_and_get() {
+    let tangle: ShardedTangle<u64> = Tangle::default().with_shards(16);
+    let parents = rand_message_ids(4);
+    let (message_id, message) = message_with_parents(&parents);
+
+    assert!(tangle.insert(message_id, message, 7).await.is_some());
+
+    assert_eq!(tangle.len().await, 5);
+    assert_eq!(tangle.get_metadata(&message_id).await, Some(7));
+    for parent in parents.iter() {
+        assert_eq!(tangle.get_children(parent).await, Some(vec![message_id]));
+    }
+}
+
+#[tokio::test]
+async fn shard_count_rounded_up_to_power_of_two() {
+    let tangle: ShardedTangle<u64> = Tangle::default().with_shards(3);
+
+    for (message_id, message) in message_chain(64) {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    assert_eq!(tangle.len().await, 65);
+    assert_eq!(tangle.stats().await.messages, 64);
+}
+
+#[tokio::test]
+async fn update_metadata_batch_across_shards() {
+    let tangle: ShardedTangle<u64> = Tangle::default().with_shards(8);
+    let messages = message_chain(32);
+
+    for (message_id, message) in messages.iter().cloned() {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    let unknown = rand_message_ids(1)[0];
+    let updates = messages
+        .iter()
+        .map(|(message_id, _)| *message_id)
+        .chain(std::iter::once(unknown))
+        .enumerate()
+        .map(|(index, message_id)| {
+            (message_id, move |metadata: &mut u64| {
+                *metadata = index as u64;
+                index
+            })
+        });
+
+    let results = tangle.update_metadata_batch(updates).await;
+
+    assert_eq!(results.len(), 33);
+    for (index, (message_id, _)) in messages.iter().enumerate() {
+        assert_eq!(results[index], Some(index));
+        assert_eq!(tangle.get_metadata(message_id).await, Some(index as u64));
+    }
+    assert_eq!(results[32], None);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_sharded_insertions() {
+    let tangle: Arc<ShardedTangle<u64>> = Arc::new(Tangle::default().with_shards(16));
+
+    let tasks = (0..8)
+        .map(|_| {
+            let tangle = tangle.clone();
+            tokio::spawn(async move {
+                let messages = message_chain(50);
+                for (message_id, message) in messages.iter().cloned() {
+                    tangle.insert(message_id, message, 0).await;
+                }
+                messages
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for task in tasks {
+        for window in task.await.unwrap().windows(2) {
+            assert_eq!(tangle.get_children(&window[0].0).await, Some(vec![window[1].0]));
+        }
+    }
+
+    assert_eq!(tangle.len().await, 8 * 51);
+}
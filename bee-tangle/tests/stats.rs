@@ -0,0 +1,29 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::message_chain;
+
+use bee_tangle::InMemoryTangle;
+
+#[tokio::test]
+async fn stats_of_the_cache() {
+    let tangle = InMemoryTangle::<u64>::default().with_shards(4).with_capacity(100);
+
+    let stats = tangle.stats().await;
+    assert_eq!((stats.vertices, stats.messages, stats.capacity), (0, 0, 100));
+    assert!(stats.eviction_threshold < stats.capacity);
+
+    for (message_id, message) in message_chain(5) {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    // The parent of the first message has a vertex but no message
+    let stats = tangle.stats().await;
+    assert_eq!((stats.vertices, stats.messages), (6, 5));
+    assert_eq!(stats.vertices, tangle.len().await);
+
+    #[cfg(not(feature = "metrics"))]
+    assert!(stats.inserts.is_none() && stats.evictions.is_none());
+}
@@ -0,0 +1,69 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{message_with_parents, MemoryHooks};
+
+use bee_tangle::Tangle;
+use bee_test::rand::message::{rand_message_id, rand_message_ids};
+
+use tokio::time::sleep;
+
+use std::{sync::Arc, time::Duration};
+
+#[tokio::test]
+async fn get_with_timeout_from_cache() {
+    let tangle = Arc::new(Tangle::<u64, _>::new(MemoryHooks::with_get_delay(Duration::from_secs(
+        10,
+    ))));
+    let (message_id, message) = message_with_parents(&rand_message_ids(1));
+
+    tangle.insert_if_absent_in_cache(message_id, message, 0).await;
+
+    let message = tangle
+        .get_with_timeout(&message_id, Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert!(message.is_some());
+}
+
+#[tokio::test]
+async fn get_with_timeout_missing() {
+    let tangle = Arc::new(Tangle::<u64, _>::new(MemoryHooks::default()));
+
+    let message = tangle
+        .get_with_timeout(&rand_message_id(), Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert!(message.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn abandoned_fetch_completes_in_background() {
+    let tangle = Arc::new(Tangle::<u64, _>::new(MemoryHooks::with_get_delay(
+        Duration::from_millis(100),
+    )));
+    let parent = rand_message_id();
+    let (message_id, message) = message_with_parents(&[parent]);
+
+    tangle
+        .hooks()
+        .messages
+        .lock()
+        .unwrap()
+        .insert(message_id, (message, 42));
+
+    assert!(tangle
+        .get_with_timeout(&message_id, Duration::from_millis(10))
+        .await
+        .is_err());
+    assert!(!tangle.contains_in_cache(&message_id).await);
+
+    sleep(Duration::from_millis(300)).await;
+
+    // The fetch went on after the timeout, and fully inserted the message
+    assert!(tangle.contains_in_cache(&message_id).await);
+    assert_eq!(tangle.get_metadata_maybe(&message_id).await, Some(42));
+    assert_eq!(tangle.get_children(&parent).await, Some(vec![message_id]));
+}
@@ -0,0 +1,36 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{message_chain, MemoryHooks};
+
+use bee_tangle::Tangle;
+
+use std::collections::HashSet;
+
+#[tokio::test]
+async fn visit_all_messages_in_chunks() {
+    let tangle = Tangle::<u64, _>::new(MemoryHooks::default()).with_shards(4);
+    let messages = message_chain(10);
+
+    for (message_id, message) in messages.iter().cloned() {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    let mut visited = Vec::new();
+    tangle
+        .visit_all(3, |chunk| {
+            assert!(!chunk.is_empty() && chunk.len() <= 3);
+            visited.extend(chunk.iter().map(|(message_id, _)| *message_id));
+        })
+        .await;
+
+    // The parent of the first message has a vertex but no message, so it is skipped
+    let expected = messages
+        .iter()
+        .map(|(message_id, _)| *message_id)
+        .collect::<HashSet<_>>();
+    assert_eq!(visited.len(), 10);
+    assert_eq!(visited.into_iter().collect::<HashSet<_>>(), expected);
+}
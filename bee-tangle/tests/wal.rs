@@ -0,0 +1,122 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{message_chain, MemoryHooks};
+
+use bee_tangle::{wal::WalManager, Hooks, NullHooks, Tangle};
+use bee_test::rand::message::rand_message_id;
+
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::atomic::Ordering,
+};
+
+// Returns a path for a log that does not exist yet.
+fn log_path() -> PathBuf {
+    std::env::temp_dir().join(format!("bee-tangle-wal-{}", rand_message_id()))
+}
+
+fn failing_hooks() -> MemoryHooks {
+    let hooks = MemoryHooks::default();
+    hooks.fail.store(true, Ordering::SeqCst);
+    hooks
+}
+
+#[tokio::test]
+async fn failed_insertions_are_replayed() {
+    let path = log_path();
+    let messages = message_chain(3);
+
+    let tangle = Tangle::<u64, _>::new(WalManager::open(&path, failing_hooks()).unwrap());
+    for (message_id, message) in messages.iter().cloned() {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    // The failed insertions survive compaction
+    assert_eq!(tangle.hooks().compact().await.unwrap(), 3);
+
+    tangle.hooks().inner().fail.store(false, Ordering::SeqCst);
+    assert_eq!(WalManager::replay(&tangle).await.unwrap(), 3);
+    assert_eq!(WalManager::replay(&tangle).await.unwrap(), 0);
+    for (message_id, _) in messages.iter() {
+        assert!(tangle.hooks().inner().contains(message_id));
+    }
+
+    assert_eq!(tangle.hooks().compact().await.unwrap(), 0);
+    assert_eq!(fs::metadata(&path).unwrap().len(), 0);
+
+    fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn failed_replay_keeps_entries() {
+    let path = log_path();
+
+    let tangle = Tangle::<u64, _>::new(WalManager::open(&path, failing_hooks()).unwrap());
+    for (message_id, message) in message_chain(2) {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    // Neither the entry that failed nor the one after it are lost
+    assert!(WalManager::replay(&tangle).await.is_err());
+    assert_eq!(tangle.hooks().compact().await.unwrap(), 2);
+
+    tangle.hooks().inner().fail.store(false, Ordering::SeqCst);
+    assert_eq!(WalManager::replay(&tangle).await.unwrap(), 2);
+
+    fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn uncommitted_entries_survive_a_crash() {
+    let path = log_path();
+    let messages = message_chain(2);
+
+    {
+        let tangle = Tangle::<u64, _>::new(WalManager::open(&path, failing_hooks()).unwrap());
+        for (message_id, message) in messages.iter().cloned() {
+            tangle.insert(message_id, message, 7).await;
+        }
+    }
+
+    // A crash while appending a record leaves it truncated, and a crash while compacting leaves the new log aside
+    OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap()
+        .write_all(&[0, 1, 2, 3])
+        .unwrap();
+    fs::write(path.with_extension("compact"), [0u8, 1]).unwrap();
+
+    let tangle = Tangle::<u64, _>::new(WalManager::open(&path, MemoryHooks::default()).unwrap());
+    assert_eq!(WalManager::replay(&tangle).await.unwrap(), 2);
+    for (message_id, _) in messages.iter() {
+        assert_eq!(tangle.hooks().inner().metadata(message_id), Some(7));
+        assert!(tangle.contains_in_cache(message_id).await);
+    }
+
+    assert_eq!(tangle.hooks().compact().await.unwrap(), 0);
+    assert!(!path.with_extension("compact").exists());
+
+    fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn nothing_is_logged_ahead_of_null_hooks() {
+    let path = log_path();
+
+    let tangle = Tangle::<u64, _>::new(WalManager::open(&path, NullHooks::<u64>::default()).unwrap());
+    assert!(Hooks::<u64>::is_noop(tangle.hooks()));
+
+    for (message_id, message) in message_chain(3) {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    assert_eq!(tangle.hooks().compact().await.unwrap(), 0);
+
+    fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,85 @@
+// Copyright 2020 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{message_chain, message_with_parents};
+
+use bee_message::MessageId;
+use bee_tangle::InMemoryTangle;
+use bee_test::rand::message::rand_message_id;
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+// Spawns a task on the current thread runtime, that can only run once the test yields to the scheduler.
+fn spawn_probe() -> Arc<AtomicBool> {
+    let ran = Arc::new(AtomicBool::new(false));
+    tokio::spawn({
+        let ran = ran.clone();
+        async move { ran.store(true, Ordering::SeqCst) }
+    });
+    ran
+}
+
+// A root approved by three children, so that the number of descendants jumps over multiples of two.
+async fn fan_out(interval: usize) -> (InMemoryTangle<u64>, MessageId) {
+    let tangle = InMemoryTangle::<u64>::default().with_yield_interval(interval);
+    let root = rand_message_id();
+
+    for _ in 0..3 {
+        let (message_id, message) = message_with_parents(&[root]);
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    (tangle, root)
+}
+
+#[tokio::test]
+async fn get_descendants_yields_on_fan_out() {
+    let (tangle, root) = fan_out(2).await;
+    let probe = spawn_probe();
+
+    assert_eq!(tangle.get_descendants(&root, 5).await.len(), 3);
+    assert!(probe.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn get_all_children_recursive_yields() {
+    let (tangle, root) = fan_out(2).await;
+    let probe = spawn_probe();
+
+    assert_eq!(tangle.get_all_children_recursive(&root, 5).await.unwrap().len(), 3);
+    assert!(probe.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn zero_interval_never_yields() {
+    let (tangle, root) = fan_out(0).await;
+    let probe = spawn_probe();
+
+    assert_eq!(tangle.get_descendants(&root, 5).await.len(), 3);
+    assert!(!probe.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn past_cone_traversal_yields() {
+    let tangle = InMemoryTangle::<u64>::default().with_yield_interval(2);
+    let messages = message_chain(4);
+
+    for (message_id, message) in messages.iter().cloned() {
+        tangle.insert(message_id, message, 0).await;
+    }
+
+    let probe = spawn_probe();
+    let (order, missing) = tangle.topological_order(&[messages[3].0]).await;
+
+    assert_eq!(
+        order,
+        messages.iter().map(|(message_id, _)| *message_id).collect::<Vec<_>>()
+    );
+    assert_eq!(missing.len(), 1);
+    assert!(probe.load(Ordering::SeqCst));
+}